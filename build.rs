@@ -0,0 +1,107 @@
+//! Build the dictionary from `assets/dict.json` into a perfect hash map,
+//! so that looking up a word needs no parsing at startup.
+//!
+//! The input is a JSON object from words in upper case to their entries.
+//! An entry is either a list of senses, or an object with a pronunciation,
+//! an etymology and the senses, all but the senses being optional.
+//! A sense is either its definition, or an object with a part of speech,
+//! the definition, examples and synonyms, all but the definition being optional:
+//!
+//! ```json
+//! {
+//!   "ABACK": ["toward or situated to the rear; back"],
+//!   "CRANE": {
+//!     "pronunciation": "kreɪn",
+//!     "senses": [
+//!       {
+//!         "part_of_speech": "noun",
+//!         "definition": "a tall, long-legged, long-necked bird",
+//!         "examples": ["a pair of cranes nested by the lake"],
+//!         "synonyms": ["heron"]
+//!       }
+//!     ],
+//!     "etymology": "Old English cran"
+//!   }
+//! }
+//! ```
+//!
+//! Every word of the builtin final list must have an entry, or the build fails.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+#[allow(dead_code)]
+mod builtin_words {
+    include!("src/builtin_words.rs");
+}
+
+mod source {
+    include!("src/dict/source.rs");
+}
+
+use source::Entry;
+
+const DICT_PATH: &str = "assets/dict.json";
+
+/// Write an entry as a Rust expression of `dict::Entry`
+fn to_code(entry: Entry) -> String {
+    let senses: Vec<String> = entry
+        .senses
+        .into_iter()
+        .map(|sense| {
+            format!(
+                "Sense {{ part_of_speech: {:?}, definition: {:?}, examples: &{:?}, synonyms: &{:?} }}",
+                sense.part_of_speech, sense.definition, sense.examples, sense.synonyms
+            )
+        })
+        .collect();
+    format!(
+        "Entry {{ pronunciation: {:?}, senses: &[{}], etymology: {:?} }}",
+        entry.pronunciation,
+        senses.join(", "),
+        entry.etymology
+    )
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed={DICT_PATH}");
+    println!("cargo:rerun-if-changed=src/builtin_words.rs");
+    println!("cargo:rerun-if-changed=src/dict/source.rs");
+
+    let contents = fs::read_to_string(DICT_PATH)
+        .unwrap_or_else(|e| panic!("failed to read {DICT_PATH}: {e}"));
+    let dict = source::parse(&contents)
+        .unwrap_or_else(|e| panic!("{DICT_PATH} is not a valid dictionary: {e}"));
+
+    // Check the dictionary before building it
+    let mut errors = vec![];
+    for word in dict.keys() {
+        if word.chars().any(|c| c.is_lowercase()) {
+            errors.push(format!("'{word}' should be in upper case"));
+        }
+    }
+    for word in builtin_words::FINAL {
+        if !dict.contains_key(&word.to_uppercase()) {
+            errors.push(format!("'{word}' of the final list has no entry"));
+        }
+    }
+    if !errors.is_empty() {
+        panic!("Invalid dictionary {DICT_PATH}:\n{}", errors.join("\n"));
+    }
+
+    let mut map = phf_codegen::Map::new();
+    for (word, entry) in dict {
+        map.entry(word, &to_code(entry));
+    }
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("dict.rs");
+    fs::write(
+        out,
+        format!(
+            "/// Entries of words in upper case\npub static DICT: phf::Map<&'static str, Entry> = {};\n",
+            map.build()
+        ),
+    )
+    .unwrap();
+}
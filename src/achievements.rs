@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::language::{self, Language};
+use crate::stats::Game;
+
+/// Achievements unlocked by finishing games
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Achievement {
+    WinInTwo,
+    TenDayStreak,
+    HardWithoutHint,
+    Alphabet,
+}
+
+/// Every achievement, in the order they are shown
+pub const ALL: [Achievement; 4] = [
+    Achievement::WinInTwo,
+    Achievement::TenDayStreak,
+    Achievement::HardWithoutHint,
+    Achievement::Alphabet,
+];
+
+/// How many days in a row games must be won for a streak
+const STREAK_DAYS: usize = 10;
+/// How many days the whole alphabet must be used within
+const ALPHABET_DAYS: i64 = 7;
+
+impl Achievement {
+    /// Get the identifier, as kept in state files
+    pub fn get_id(self) -> &'static str {
+        match self {
+            Self::WinInTwo => "win_in_two",
+            Self::TenDayStreak => "ten_day_streak",
+            Self::HardWithoutHint => "hard_without_hint",
+            Self::Alphabet => "alphabet",
+        }
+    }
+
+    /// Find an achievement by its identifier
+    pub fn from_id(id: &str) -> Option<Self> {
+        ALL.into_iter().find(|achievement| achievement.get_id() == id)
+    }
+
+    /// Get the name shown to players
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::WinInTwo => "Win in 2",
+            Self::TenDayStreak => "10-Day Streak",
+            Self::HardWithoutHint => "On My Own",
+            Self::Alphabet => "Alphabet Soup",
+        }
+    }
+
+    /// Get what to do to unlock the achievement
+    pub fn get_description(self) -> &'static str {
+        match self {
+            Self::WinInTwo => "Win a game within 2 guesses",
+            Self::TenDayStreak => "Win games on 10 days in a row",
+            Self::HardWithoutHint => "Win a game in hard mode without any hint",
+            Self::Alphabet => "Use every letter of the alphabet within a week",
+        }
+    }
+
+    /// Check if the last game of the history, played in a language, unlocks the achievement.
+    /// Only games with details recorded count for rules on those details
+    fn is_unlocked_by(self, games: &[Game], language: &Language) -> bool {
+        let last = match games.last() {
+            Some(game) => game,
+            None => return false,
+        };
+        match self {
+            Self::WinInTwo => is_won(last) && last.guesses.len() <= 2,
+            Self::TenDayStreak => is_won(last) && get_streak(games) >= STREAK_DAYS,
+            Self::HardWithoutHint => {
+                is_won(last) && last.difficult == Some(true) && last.hinted == Some(false)
+            }
+            Self::Alphabet => uses_alphabet(games, language),
+        }
+    }
+}
+
+/// Check if a game was won
+fn is_won(game: &Game) -> bool {
+    game.guesses.last() == Some(&game.answer)
+}
+
+/// Count the days in a row on which games were won, until the day of the last game
+fn get_streak(games: &[Game]) -> usize {
+    let last_day = match games.last().and_then(|game| game.date) {
+        Some(date) => date.date_naive(),
+        None => return 0,
+    };
+    let days: HashSet<NaiveDate> = games
+        .iter()
+        .filter(|game| is_won(game))
+        .filter_map(|game| game.date)
+        .map(|date| date.date_naive())
+        .collect();
+
+    let mut streak = 0;
+    let mut day = last_day;
+    while days.contains(&day) {
+        streak += 1;
+        day = match day.pred_opt() {
+            Some(day) => day,
+            None => break,
+        };
+    }
+    streak
+}
+
+/// Check if every letter of the alphabet was used in guesses during the week until the last game,
+/// where accented letters count as their base letters
+fn uses_alphabet(games: &[Game], language: &Language) -> bool {
+    let last_date = match games.last().and_then(|game| game.date) {
+        Some(date) => date,
+        None => return false,
+    };
+    let since = last_date - Duration::days(ALPHABET_DAYS);
+    let letters: HashSet<usize> = games
+        .iter()
+        .filter(|game| game.date.is_some_and(|date| date > since))
+        .flat_map(|game| game.guesses.iter())
+        .flat_map(|guess| language::letters(guess))
+        .filter_map(|letter| language.get_index(letter))
+        .collect();
+    letters.len() == language.get_size()
+}
+
+/// Get the achievements unlocked by the last game of the history, played in a language,
+/// except those already unlocked
+pub fn check(games: &[Game], unlocked: &[Achievement], language: &Language) -> Vec<Achievement> {
+    ALL.into_iter()
+        .filter(|achievement| !unlocked.contains(achievement))
+        .filter(|achievement| achievement.is_unlocked_by(games, language))
+        .collect()
+}
+
+/// Print achievements just unlocked, in tty mode or as plain lines
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print_unlocked(achievements: &[Achievement], is_tty: bool) {
+    for achievement in achievements {
+        if is_tty {
+            println!(
+                "{} {}: {}",
+                console::style("Achievement unlocked!").bold().yellow(),
+                console::style(achievement.get_name()).bold().magenta(),
+                achievement.get_description()
+            );
+        } else {
+            println!("ACHIEVEMENT {}", achievement.get_id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+    use crate::storage::{JsonStorage, Storage};
+
+    /// A game with every detail recorded, played some days ago
+    fn game(answer: &str, guesses: &[&str], days_ago: i64, difficult: bool, hinted: bool) -> Game {
+        Game {
+            answer: answer.to_string(),
+            guesses: guesses.iter().map(|guess| guess.to_string()).collect(),
+            date: Some(Utc::now() - Duration::days(days_ago)),
+            difficult: Some(difficult),
+            word_list: Some("builtin".to_string()),
+            hinted: Some(hinted),
+        }
+    }
+
+    /// Save games to a JSON state file and load them back, as they are after a restart
+    fn reload(name: &str, games: &[Game]) -> Vec<Game> {
+        let path = std::env::temp_dir().join(format!("wordle-{}-{name}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut storage = JsonStorage::open(&path).unwrap();
+        storage.append_players(&[(None, games.to_vec())]).unwrap();
+        let games = storage.load(&None).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        games
+    }
+
+    fn check_reloaded(name: &str, games: &[Game], language: &str) -> Vec<Achievement> {
+        check(&reload(name, games), &[], language::get(language))
+    }
+
+    #[test]
+    fn ten_day_streak_after_reload() {
+        let games: Vec<Game> = (0..10)
+            .rev()
+            .map(|days_ago| game("CRANE", &["SLATE", "CRANE"], days_ago, false, false))
+            .collect();
+        assert!(check_reloaded("streak", &games, "en").contains(&Achievement::TenDayStreak));
+
+        // A day missed breaks the streak
+        let games: Vec<Game> = (0..11)
+            .rev()
+            .filter(|&days_ago| days_ago != 4)
+            .map(|days_ago| game("CRANE", &["SLATE", "CRANE"], days_ago, false, false))
+            .collect();
+        assert!(!check_reloaded("no-streak", &games, "en").contains(&Achievement::TenDayStreak));
+    }
+
+    #[test]
+    fn hard_without_hint_after_reload() {
+        let games = [game("CRANE", &["SLATE", "TRACE", "CRANE"], 0, true, false)];
+        assert!(check_reloaded("hard", &games, "en").contains(&Achievement::HardWithoutHint));
+
+        let games = [game("CRANE", &["SLATE", "TRACE", "CRANE"], 0, true, true)];
+        assert!(!check_reloaded("hinted", &games, "en").contains(&Achievement::HardWithoutHint));
+    }
+
+    #[test]
+    fn alphabet_after_reload() {
+        let guesses = ["ABCDE", "FGHIJ", "KLMNO", "PQRST", "UVWXY", "ZZZZZ"];
+        let games = [
+            game("ZZZZZ", &guesses[..3], 3, false, false),
+            game("ZZZZZ", &guesses[3..], 0, false, false),
+        ];
+        assert!(check_reloaded("alphabet", &games, "en").contains(&Achievement::Alphabet));
+
+        // Letters used more than a week ago don't count
+        let games = [
+            game("ZZZZZ", &guesses[..3], 8, false, false),
+            game("ZZZZZ", &guesses[3..], 0, false, false),
+        ];
+        assert!(!check_reloaded("old-letters", &games, "en").contains(&Achievement::Alphabet));
+    }
+
+    #[test]
+    fn alphabet_of_language() {
+        let guesses = ["ÁBCDE", "FGHIJ", "KLMNO", "PQRST", "UVWXY", "ZZZZZ"];
+        let games = [game("ZZZZZ", &guesses, 0, false, false)];
+        // 'Ñ' is a letter of its own in Spanish, while 'Á' counts as 'A'
+        assert!(!check_reloaded("spanish", &games, "es").contains(&Achievement::Alphabet));
+
+        let games = [
+            game("ZZZZZ", &guesses, 1, false, false),
+            game("ÑOÑOS", &["ÑÑÑÑÑ"], 0, false, false),
+        ];
+        assert!(check_reloaded("spanish-ñ", &games, "es").contains(&Achievement::Alphabet));
+    }
+}
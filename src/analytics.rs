@@ -0,0 +1,498 @@
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{collections::BTreeSet, path::Path};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::args::BUILTIN_WORD_LIST;
+use crate::game::{self, LetterStatus, WORD_LENGTH};
+use crate::language;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::language::{Language, DEFAULT_LANGUAGE};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{packs, words};
+use crate::stats::Game;
+use crate::words::Metadata;
+
+/// How many guesses to track the remaining candidates after
+pub const TRACKED_GUESSES: usize = 2;
+
+/// A word used to open games, and how those games went
+pub struct Opening {
+    pub word: String,
+    pub games: usize,
+    pub wins: usize,
+    tries: usize,
+}
+
+impl Opening {
+    /// Get average tries of games won with this opening
+    pub fn get_average_tries(&self) -> f64 {
+        get_average_tries(self.tries, self.wins)
+    }
+}
+
+/// A tag of answers, and how games with answers of the tag went
+pub struct Tag {
+    pub tag: String,
+    pub games: usize,
+    pub wins: usize,
+    tries: usize,
+}
+
+impl Tag {
+    /// Get average tries of games won with answers of this tag
+    pub fn get_average_tries(&self) -> f64 {
+        get_average_tries(self.tries, self.wins)
+    }
+}
+
+/// Get average tries of won games, or 0 if none was won
+fn get_average_tries(tries: usize, wins: usize) -> f64 {
+    if wins == 0 {
+        0.0
+    } else {
+        tries as f64 / wins as f64
+    }
+}
+
+/// An answer which was hard to find. `tries` is None if the game was failed
+pub struct Hardest {
+    pub answer: String,
+    pub tries: Option<usize>,
+}
+
+/// Analytics computed from the game history
+pub struct Analytics {
+    /// Most common opening words
+    pub openings: Vec<Opening>,
+    /// Answers that took the most guesses, failed ones first
+    pub hardest: Vec<Hardest>,
+    /// Most common tags of answers, as told by the metadata of word lists
+    pub tags: Vec<Tag>,
+    /// Letters of answers most often never placed correctly
+    pub missed_letters: Vec<(String, usize)>,
+    /// Average count of answers still possible after each of the first guesses.
+    /// None if no game has gone that far
+    pub remaining: [Option<f64>; TRACKED_GUESSES],
+    /// Games left out of remaining candidates, as their answer lists could not be loaded
+    pub skipped: usize,
+}
+
+impl Analytics {
+    /// Compute analytics of games, keeping the top entries of each list.
+    /// Candidates are counted in the answer list each game was recorded with,
+    /// or in the default one for games recorded without it
+    pub fn new(
+        games: &[Game],
+        answer_lists: &HashMap<String, Vec<String>>,
+        default: &[String],
+        metadata: &Metadata,
+        top: usize,
+    ) -> Self {
+        let (remaining, skipped) = get_remaining(games, answer_lists, default);
+        Self {
+            openings: get_openings(games, top),
+            hardest: get_hardest(games, top),
+            tags: get_tags(games, metadata, top),
+            missed_letters: get_missed_letters(games, top),
+            remaining,
+            skipped,
+        }
+    }
+}
+
+/// Check if a game was won
+fn is_won(game: &Game) -> bool {
+    game.guesses.last() == Some(&game.answer)
+}
+
+/// Most common opening words, along with the outcome of those games
+fn get_openings(games: &[Game], top: usize) -> Vec<Opening> {
+    let mut openings: HashMap<&String, Opening> = HashMap::new();
+    for game in games {
+        let word = match game.guesses.first() {
+            Some(word) => word,
+            None => continue,
+        };
+        let opening = openings.entry(word).or_insert_with(|| Opening {
+            word: word.to_string(),
+            games: 0,
+            wins: 0,
+            tries: 0,
+        });
+        opening.games += 1;
+        if is_won(game) {
+            opening.wins += 1;
+            opening.tries += game.guesses.len();
+        }
+    }
+
+    let mut openings: Vec<Opening> = openings.into_values().collect();
+    openings.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.word.cmp(&b.word)));
+    openings.truncate(top);
+    openings
+}
+
+/// Answers that took the most guesses, failed ones first
+fn get_hardest(games: &[Game], top: usize) -> Vec<Hardest> {
+    let mut hardest: Vec<Hardest> = games
+        .iter()
+        .map(|game| Hardest {
+            answer: game.answer.to_string(),
+            tries: is_won(game).then_some(game.guesses.len()),
+        })
+        .collect();
+    // Failed games are treated as taking infinite guesses
+    hardest.sort_by(|a, b| {
+        let a_tries = a.tries.unwrap_or(usize::MAX);
+        let b_tries = b.tries.unwrap_or(usize::MAX);
+        b_tries.cmp(&a_tries).then_with(|| a.answer.cmp(&b.answer))
+    });
+    hardest.truncate(top);
+    hardest
+}
+
+/// Most common tags of answers, along with the outcome of those games
+fn get_tags(games: &[Game], metadata: &Metadata, top: usize) -> Vec<Tag> {
+    let mut tags: HashMap<&String, Tag> = HashMap::new();
+    for game in games {
+        let entry = match metadata.get(&game.answer) {
+            Some(entry) => entry,
+            None => continue,
+        };
+        for name in &entry.tags {
+            let tag = tags.entry(name).or_insert_with(|| Tag {
+                tag: name.to_string(),
+                games: 0,
+                wins: 0,
+                tries: 0,
+            });
+            tag.games += 1;
+            if is_won(game) {
+                tag.wins += 1;
+                tag.tries += game.guesses.len();
+            }
+        }
+    }
+
+    let mut tags: Vec<Tag> = tags.into_values().collect();
+    tags.sort_by(|a, b| b.games.cmp(&a.games).then_with(|| a.tag.cmp(&b.tag)));
+    tags.truncate(top);
+    tags
+}
+
+/// Letters of answers which were never placed correctly during a game
+fn get_missed_letters(games: &[Game], top: usize) -> Vec<(String, usize)> {
+    let mut missed: HashMap<&str, usize> = HashMap::new();
+    for game in games {
+        let mut found = [false; WORD_LENGTH];
+        for guess in &game.guesses {
+            let status = game::evaluate(&game.answer, guess);
+            for (i, letter_status) in status.iter().enumerate() {
+                found[i] |= *letter_status == LetterStatus::Green;
+            }
+        }
+        for (i, c) in language::letters(&game.answer).enumerate() {
+            if !found[i] {
+                *missed.entry(c).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let mut missed: Vec<(String, usize)> = missed
+        .into_iter()
+        .map(|(c, count)| (c.to_string(), count))
+        .collect();
+    missed.sort_by(|(c1, cnt1), (c2, cnt2)| cnt2.cmp(cnt1).then_with(|| c1.cmp(c2)));
+    missed.truncate(top);
+    missed
+}
+
+/// Load the answer lists games were recorded with, by their names.
+/// Lists which can no longer be found, such as removed files, are left out
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_answer_lists(
+    games: &[Game],
+    language: &Language,
+    pack_dir: Option<&Path>,
+) -> HashMap<String, Vec<String>> {
+    let names: BTreeSet<&String> =
+        games.iter().filter_map(|game| game.word_list.as_ref()).collect();
+    names
+        .into_iter()
+        .filter_map(|name| Some((name.clone(), load_answer_list(name, language, pack_dir)?)))
+        .collect()
+}
+
+/// Load an answer list by the name recorded along with games
+#[cfg(not(target_arch = "wasm32"))]
+fn load_answer_list(
+    name: &str,
+    language: &Language,
+    pack_dir: Option<&Path>,
+) -> Option<Vec<String>> {
+    if name == BUILTIN_WORD_LIST {
+        return Some(language::get(DEFAULT_LANGUAGE).get_final());
+    }
+    if let Some(name) = name.strip_prefix(BUILTIN_WORD_LIST).and_then(|n| n.strip_prefix(':')) {
+        return language::find(name).map(Language::get_final);
+    }
+    if let Some(name) = packs::parse_word_list_name(name) {
+        let pack = packs::find(name, pack_dir, language).ok()?;
+        return Some(pack.get_answers(language));
+    }
+    let entries = words::read_entries(Path::new(name), language).ok()?;
+    Some(words::get_answers(&entries))
+}
+
+/// Average count of answers still possible after each of the first guesses,
+/// along with how many games were skipped as their answer lists are unknown
+fn get_remaining(
+    games: &[Game],
+    answer_lists: &HashMap<String, Vec<String>>,
+    default: &[String],
+) -> ([Option<f64>; TRACKED_GUESSES], usize) {
+    let mut total = [0usize; TRACKED_GUESSES];
+    let mut counted = [0usize; TRACKED_GUESSES];
+    let mut skipped = 0;
+    for game in games {
+        let answer_list = match &game.word_list {
+            Some(name) => match answer_lists.get(name) {
+                Some(list) => list,
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            },
+            None => default,
+        };
+        // Narrow down the candidates guess by guess
+        let mut candidates: Vec<&String> = answer_list.iter().collect();
+        for (round, guess) in game.guesses.iter().take(TRACKED_GUESSES).enumerate() {
+            let status = game::evaluate(&game.answer, guess);
+            candidates.retain(|candidate| game::evaluate(candidate, guess) == status);
+            total[round] += candidates.len();
+            counted[round] += 1;
+        }
+    }
+
+    let mut remaining = [None; TRACKED_GUESSES];
+    for round in 0..TRACKED_GUESSES {
+        if counted[round] > 0 {
+            remaining[round] = Some(total[round] as f64 / counted[round] as f64);
+        }
+    }
+    (remaining, skipped)
+}
+
+/// Print analytics, in tty mode or as plain lines
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print(analytics: &Analytics, is_tty: bool) {
+    let remaining: Vec<String> = analytics
+        .remaining
+        .iter()
+        .map(|r| r.map_or("-".to_string(), |r| format!("{r:.2}")))
+        .collect();
+
+    if is_tty {
+        println!(
+            "{}",
+            console::style("Most common opening words:").bold().blue()
+        );
+        for opening in &analytics.openings {
+            println!(
+                "    {}: {} games, {} won, {:.2} tries on average",
+                console::style(&opening.word).bold().magenta(),
+                opening.games,
+                opening.wins,
+                opening.get_average_tries()
+            );
+        }
+        println!("{}", console::style("Hardest answers:").bold().blue());
+        for hardest in &analytics.hardest {
+            let result = match hardest.tries {
+                Some(tries) => format!("won in {tries} guesses"),
+                None => "failed".to_string(),
+            };
+            println!(
+                "    {}: {result}",
+                console::style(&hardest.answer).bold().magenta()
+            );
+        }
+        // Tags are only known with word lists with metadata
+        if !analytics.tags.is_empty() {
+            println!("{}", console::style("Most common answer tags:").bold().blue());
+            for tag in &analytics.tags {
+                println!(
+                    "    {}: {} games, {} won, {:.2} tries on average",
+                    console::style(&tag.tag).bold().magenta(),
+                    tag.games,
+                    tag.wins,
+                    tag.get_average_tries()
+                );
+            }
+        }
+        println!(
+            "{}",
+            console::style("Most often missed letters:").bold().blue()
+        );
+        for (letter, count) in &analytics.missed_letters {
+            println!(
+                "    {}: missed {count} times",
+                console::style(letter).bold().magenta()
+            );
+        }
+        println!(
+            "{}",
+            console::style("Average remaining candidates:")
+                .bold()
+                .blue()
+        );
+        for (round, remaining) in remaining.iter().enumerate() {
+            println!("    After guess {}: {remaining}", round + 1);
+        }
+        if analytics.skipped > 0 {
+            println!(
+                "{}",
+                console::style(format!(
+                    "    {} games left out, as their word lists could not be loaded",
+                    analytics.skipped
+                ))
+                .yellow()
+            );
+        }
+    } else {
+        let openings = analytics.openings.iter().map(|opening| {
+            format!(
+                " {} {} {} {:.2}",
+                opening.word,
+                opening.games,
+                opening.wins,
+                opening.get_average_tries()
+            )
+        });
+        println!("OPENINGS{}", String::from_iter(openings));
+
+        let hardest = analytics.hardest.iter().map(|hardest| match hardest.tries {
+            Some(tries) => format!(" {} {tries}", hardest.answer),
+            None => format!(" {} X", hardest.answer),
+        });
+        println!("HARDEST{}", String::from_iter(hardest));
+
+        let tags = analytics.tags.iter().map(|tag| {
+            format!(
+                " {} {} {} {:.2}",
+                tag.tag,
+                tag.games,
+                tag.wins,
+                tag.get_average_tries()
+            )
+        });
+        println!("TAGS{}", String::from_iter(tags));
+
+        let missed = analytics
+            .missed_letters
+            .iter()
+            .map(|(letter, count)| format!(" {letter} {count}"));
+        println!("MISSED{}", String::from_iter(missed));
+
+        println!("REMAINING {}", remaining.join(" "));
+        if analytics.skipped > 0 {
+            println!("SKIPPED {}", analytics.skipped);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A game played with a recorded answer list
+    fn game(answer: &str, guesses: &[&str], word_list: Option<&str>) -> Game {
+        Game {
+            answer: answer.to_string(),
+            guesses: guesses.iter().map(|guess| guess.to_string()).collect(),
+            date: None,
+            difficult: None,
+            word_list: word_list.map(str::to_string),
+            hinted: None,
+        }
+    }
+
+    fn list(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn openings() {
+        let games = [
+            game("CRANE", &["SLATE", "CRANE"], None),
+            game("LIGHT", &["SLATE", "NIGHT"], None),
+            game("CRANE", &["CRANE"], None),
+            game("HELLO", &[], None),
+        ];
+        let openings = get_openings(&games, 5);
+        let summary: Vec<(&str, usize, usize, f64)> = openings
+            .iter()
+            .map(|o| (o.word.as_str(), o.games, o.wins, o.get_average_tries()))
+            .collect();
+        assert_eq!(summary, [("SLATE", 2, 1, 2.0), ("CRANE", 1, 1, 1.0)]);
+        assert_eq!(get_openings(&games, 1).len(), 1);
+    }
+
+    #[test]
+    fn hardest_answers() {
+        let games = [
+            game("CRANE", &["SLATE", "CRANE"], None),
+            game("LIGHT", &["SLATE", "NIGHT"], None),
+            game("HELLO", &["SLATE", "CRANE", "JELLY", "HELLO"], None),
+            game("ABOUT", &["ABOUT"], None),
+        ];
+        let hardest: Vec<(String, Option<usize>)> = get_hardest(&games, 3)
+            .into_iter()
+            .map(|h| (h.answer, h.tries))
+            .collect();
+        // Failed games come first
+        assert_eq!(
+            hardest,
+            [
+                ("LIGHT".to_string(), None),
+                ("HELLO".to_string(), Some(4)),
+                ("CRANE".to_string(), Some(2))
+            ]
+        );
+    }
+
+    #[test]
+    fn missed_letters() {
+        let games = [
+            // Won games have every letter placed
+            game("CRANE", &["SLATE", "CRANE"], None),
+            game("LIGHT", &["SLATE", "NIGHT"], None),
+            game("LUCKY", &["LUNCH"], None),
+            // Letters found in the wrong place are still missed
+            game("LEMON", &["MELON"], None),
+        ];
+        let missed = get_missed_letters(&games, 3);
+        assert_eq!(
+            missed,
+            [("L".to_string(), 2), ("C".to_string(), 1), ("K".to_string(), 1)]
+        );
+    }
+
+    #[test]
+    fn remaining_in_recorded_lists() {
+        let games = [
+            game("CRANE", &["CRATE", "CRANE"], Some("small")),
+            // Games recorded without their lists are counted in the default one
+            game("HELLO", &["CRANE", "HELLO"], None),
+            game("CRANE", &["SLATE"], Some("removed.txt")),
+        ];
+        let answer_lists =
+            HashMap::from([("small".to_string(), list(&["CRANE", "CRATE", "SLATE"]))]);
+        let default = list(&["CRANE", "HELLO", "JELLY"]);
+        let (remaining, skipped) = get_remaining(&games, &answer_lists, &default);
+        assert_eq!(remaining, [Some(1.5), Some(1.0)]);
+        assert_eq!(skipped, 1);
+    }
+}
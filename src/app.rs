@@ -1,29 +1,51 @@
 use egui::{FontData, FontDefinitions, FontFamily, Frame, Pos2, RichText, Window};
-use rand::seq::SliceRandom;
-use rand::SeedableRng;
+#[cfg(target_arch = "wasm32")]
+use rand::{seq::SliceRandom, SeedableRng};
 
+mod achievements;
 mod colors;
 mod definition;
 mod grid;
 mod keyboard;
 mod letter;
 mod metrics;
+#[cfg(not(target_arch = "wasm32"))]
+mod race;
+#[cfg(not(target_arch = "wasm32"))]
+mod replay;
 mod settings;
 mod stats;
 mod utils;
 mod visuals;
 
+use crate::achievements::Achievement;
+use crate::analytics::Analytics;
 use crate::args::{self, Args};
-use crate::builtin_words;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::args::Strategy;
+use crate::dict::Dictionary;
+use crate::packs;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::replay::Replay;
 use crate::game::{Game, GameStatus};
+use crate::language;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::selection;
 use crate::stats::Stats;
+use crate::words::Metadata;
 
+use achievements::achievements;
 use definition::definition;
 use grid::grid;
 use keyboard::keyboard;
 use settings::settings;
 use stats::stats;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use race::Race;
+#[cfg(not(target_arch = "wasm32"))]
+use replay::Playback;
+
 /// App state persistence
 #[derive(serde::Deserialize, serde::Serialize)]
 pub struct WordleApp {
@@ -36,6 +58,33 @@ pub struct WordleApp {
     word_list: Vec<String>,
     #[serde(skip)]
     answer_list: Vec<String>,
+    /// The pack, language and seed the word lists are loaded for
+    #[serde(skip)]
+    loaded: (Option<String>, Option<String>, Option<u64>),
+    #[serde(skip)]
+    metadata: Metadata,
+    #[serde(skip)]
+    stats_controls: stats::Controls,
+    #[serde(skip)]
+    analytics: Option<Analytics>,
+    #[serde(skip)]
+    unlocked: Vec<Achievement>,
+    #[serde(skip)]
+    dictionary: Dictionary,
+    /// The guessed word clicked to show its definition
+    #[serde(skip)]
+    defined: Option<String>,
+    /// Whether to open the definition panel on next frame
+    #[serde(skip)]
+    open_definition: bool,
+    /// The race joined, which takes the place of the game
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    race: Option<Race>,
+    /// The race played back, which takes the place of the game as well
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    playback: Option<Playback>,
 }
 
 impl Default for WordleApp {
@@ -48,13 +97,25 @@ impl Default for WordleApp {
             guess: "".to_string(),
             word_list: vec![],
             answer_list: vec![],
+            loaded: (None, None, None),
+            metadata: Metadata::new(),
+            stats_controls: stats::Controls::default(),
+            analytics: None,
+            unlocked: vec![],
+            dictionary: Dictionary::default(),
+            defined: None,
+            open_definition: false,
+            #[cfg(not(target_arch = "wasm32"))]
+            race: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            playback: None,
         }
     }
 }
 
 impl WordleApp {
     /// App initialization
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, dictionary: Dictionary) -> Self {
         // Load fonts
         let mut fonts = FontDefinitions::default();
 
@@ -96,6 +157,8 @@ impl WordleApp {
             Default::default()
         };
 
+        app.dictionary = dictionary;
+
         // Load default args
         if app.args.seed.is_none() {
             app.args.seed = Some(args::DEFAULT_SEED);
@@ -105,16 +168,7 @@ impl WordleApp {
         }
 
         // Load word lists
-        app.word_list = builtin_words::ACCEPTABLE
-            .iter()
-            .map(|s| s.to_uppercase())
-            .collect();
-        app.answer_list = builtin_words::FINAL
-            .iter()
-            .map(|s| s.to_uppercase())
-            .collect();
-
-        app.shuffle_answer_list(app.args.seed.unwrap());
+        app.load_word_lists();
 
         // Start a new game when first run
         if app.game.is_none() {
@@ -124,29 +178,94 @@ impl WordleApp {
         app
     }
 
-    /// Shuffle the answer list
-    fn shuffle_answer_list(&mut self, seed: u64) {
-        // Sort the answer list first to produce reproducible results
-        self.answer_list.sort();
+    /// Get the pack, language and seed chosen, which the word lists are loaded for
+    fn get_lists_chosen(&self) -> (Option<String>, Option<String>, Option<u64>) {
+        (
+            self.args.pack.clone(),
+            self.args.language.clone(),
+            self.args.seed,
+        )
+    }
+
+    /// Load the word lists of the pack chosen, or the builtin ones of the language,
+    /// and order the answer list by the seed
+    fn load_word_lists(&mut self) {
+        let language = self.args.get_language();
+
+        // A pack saved by a former version may have gone, or be in another language
+        let pack = self.args.pack.as_ref().and_then(|name| {
+            packs::builtin()
+                .into_iter()
+                .find(|pack| &pack.name == name && pack.check_language(language).is_ok())
+        });
+        if pack.is_none() {
+            self.args.pack = None;
+        }
 
-        let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
-        self.answer_list.shuffle(&mut rng);
+        let answers = if let Some(pack) = pack {
+            self.word_list = pack.get_acceptable(language);
+            pack.get_answers(language)
+        } else {
+            self.word_list = language.get_acceptable();
+            language.get_final()
+        };
+        // Sort the word list to accelerate search
+        self.word_list.sort();
+
+        // Answers are ordered as in random mode of the CLI, so that a seed and a day
+        // give the same answer in both
+        let seed = self.args.seed.unwrap();
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.answer_list =
+                selection::order(answers, Strategy::Uniform, seed, &self.metadata, 0.0);
+        }
+        // Only the uniform strategy is there on the web, which just shuffles the answers
+        #[cfg(target_arch = "wasm32")]
+        {
+            self.answer_list = answers;
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            self.answer_list.shuffle(&mut rng);
+        }
+        self.loaded = self.get_lists_chosen();
     }
 
-    /// Start a new game at specified day
+    /// Start a new game at specified day, loading the word lists first
+    /// if the pack, language or seed has been changed since
     fn start(&mut self) {
-        let day = self.args.day.unwrap();
+        // Word lists are only swapped between games, as the game going checks guesses with them
+        if self.loaded != self.get_lists_chosen() {
+            self.load_word_lists();
+        }
+        // The day may have been chosen with the former answer list
+        let day = self.args.day.unwrap() % self.answer_list.len() as u32;
+        self.args.day = Some(day);
 
         self.game = Game::new(
             &self.answer_list[day as usize],
             self.args.difficult,
+            self.args.ignore_accents,
             &self.answer_list,
+            self.args.get_language(),
         )
         .ok();
 
         self.game_status = Some(GameStatus::Going);
 
         self.guess.clear();
+        self.defined = None;
+    }
+
+    /// Race with others instead of playing alone
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn join(&mut self, race: Race) {
+        self.race = Some(race);
+    }
+
+    /// Play back a race instead of playing
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn play(&mut self, replay: Replay) {
+        self.playback = Some(Playback::new(replay));
     }
 
     /// Increase the day count
@@ -183,6 +302,24 @@ impl eframe::App for WordleApp {
         });
 
         egui::CentralPanel::default().show(ctx, |ui| {
+            // Races are played with the host, leaving the game and its panels alone
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(ref mut race) = self.race {
+                Frame::window(ui.style()).show(ui, |ui| {
+                    race::panel(ui, race, self.args.difficult);
+                });
+                race::race(ui, race, self.args.difficult);
+                return;
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(ref mut playback) = self.playback {
+                Frame::window(ui.style()).show(ui, |ui| {
+                    replay::panel(ui, playback);
+                });
+                replay::replay(ui, playback, self.args.difficult);
+                return;
+            }
+
             // Use a window to contain the panels on a phone
             if utils::is_phone(ui) {
                 Window::new(RichText::new("Explore").size(metrics::PANEL_TITLE_FONT_SIZE))
@@ -196,10 +333,13 @@ impl eframe::App for WordleApp {
                         settings(ui, self);
 
                         // Stats panel
-                        stats(ui, self.args.difficult, &self.stats);
+                        stats(ui, self);
 
                         // Definition panel
                         definition(ui, self);
+
+                        // Achievements panel
+                        achievements(ui, self);
                     });
             } else {
                 // Setting panel
@@ -209,26 +349,38 @@ impl eframe::App for WordleApp {
 
                 // Stats panel
                 Frame::window(ui.style()).show(ui, |ui| {
-                    stats(ui, self.args.difficult, &self.stats);
+                    stats(ui, self);
                 });
 
                 // Definition panel
                 Frame::window(ui.style()).show(ui, |ui| {
                     definition(ui, self);
                 });
+
+                // Achievements panel
+                Frame::window(ui.style()).show(ui, |ui| {
+                    achievements(ui, self);
+                });
             }
 
+            // Notify achievements just unlocked
+            achievements::notification(ui.ctx(), &mut self.unlocked);
+
             // We are in a game now
             let game = self.game.as_mut().unwrap();
 
             // The letter grid
-            grid(ui, game, &mut self.guess, self.args.difficult);
+            if let Some(word) = grid(ui, game.get_guesses(), &self.guess, self.args.difficult) {
+                self.defined = Some(word);
+                self.open_definition = true;
+            }
 
             // Render the keyboard and get keyboard input
             if let Some(key) = keyboard(
                 ui,
                 self.args.difficult,
                 game.get_alphabet(),
+                game.get_language(),
                 self.game_status.as_ref().unwrap(),
                 game.validate_guess(self.args.difficult, false, &self.guess, &self.word_list)
                     .is_ok(),
@@ -236,48 +388,68 @@ impl eframe::App for WordleApp {
             ) {
                 // Check if hint button is hit
                 if self.guess == "HINT" && key == keyboard::ENTER {
-                    self.guess = game.get_hint(&self.word_list).to_string();
+                    // The hint button stays if no word is left for a hint
+                    if let Some(hint) = game.get_hint(&self.word_list) {
+                        self.guess = hint;
+                        self.stats.use_hint();
+                    }
                     // Prevent further processing because if not,
                     // the hint will be submitted immediately
                     return;
                 }
                 if self.game_status == Some(GameStatus::Going) {
-                    match key {
+                    match key.as_str() {
                         // Guess
                         keyboard::ENTER => {
-                            let result = game.guess(&self.guess, &self.word_list);
-                            match result {
-                                Ok(game_status) => {
-                                    // Update stats
-                                    self.stats.update_guess(&self.guess);
-                                    match &game_status {
-                                        GameStatus::Won(round) => {
-                                            self.stats.win_with_guesses_updated(*round)
-                                        }
-                                        GameStatus::Failed(_) => {
-                                            self.stats.fail_with_guesses_updated()
-                                        }
-                                        GameStatus::Going => (),
+                            // Do nothing if the guess is invalid because we've indicated it by the enter button
+                            if let Ok(game_status) = game.guess(&self.guess, &self.word_list) {
+                                // Update stats, with the guess as spelled in the word list
+                                self.stats.update_guess(&game.get_guesses().last().unwrap().0);
+                                match &game_status {
+                                    GameStatus::Won(round) => {
+                                        self.stats.win_with_guesses_updated(*round)
+                                    }
+                                    GameStatus::Failed(_) => {
+                                        self.stats.fail_with_guesses_updated()
                                     }
+                                    GameStatus::Going => (),
+                                }
+                                if game_status != GameStatus::Going {
+                                    self.stats.set_mode(
+                                        self.args.difficult,
+                                        &self.args.get_word_list_name(),
+                                    );
+                                    self.stats
+                                        .record_game(game.get_answer(), game.get_guesses());
+                                    // Stats of the GUI are kept by eframe instead of a storage,
+                                    // so there is nothing to fail in saving them
+                                    let unlocked = self
+                                        .stats
+                                        .unlock_achievements(game.get_language())
+                                        .unwrap_or_default();
+                                    self.unlocked.extend(unlocked);
+                                    // Analytics are outdated now
+                                    self.analytics = None;
+                                }
 
-                                    // Clear guess for next guess to use
-                                    self.guess.clear();
+                                // Clear guess for next guess to use
+                                self.guess.clear();
 
-                                    // Save game status
-                                    self.game_status = Some(game_status);
-                                }
-                                // Do nothing because we've indicated the guess is invalid by the enter button
-                                Err(_) => (),
+                                // Save game status
+                                self.game_status = Some(game_status);
                             }
                         }
                         keyboard::BACKSPACE => {
-                            self.guess.pop();
+                            // Remove the last letter, which may be of several chars
+                            if let Some(last) = language::letters(&self.guess).last() {
+                                self.guess.truncate(self.guess.len() - last.len());
+                            }
                         }
                         // Enter a letter
                         _ => {
                             // Avoid entering more than 5 letters
-                            if self.guess.len() < metrics::COLUMNS as usize {
-                                self.guess.push(key);
+                            if language::count_letters(&self.guess) < metrics::COLUMNS as usize {
+                                self.guess.push_str(&key);
                             }
                         }
                     }
@@ -0,0 +1,55 @@
+use egui::{Align2, RichText, Window};
+
+use crate::achievements::{self as rules, Achievement};
+
+use super::{colors, metrics, WordleApp};
+
+/// Achievements panel
+pub fn achievements(ui: &mut egui::Ui, app: &WordleApp) {
+    ui.collapsing("Achievements", |ui| {
+        ui.set_max_width(metrics::PANEL_WIDTH);
+
+        let unlocked = app.stats.get_achievements();
+        ui.label(format!(
+            "Unlocked: {} / {}",
+            unlocked.len(),
+            rules::ALL.len()
+        ));
+
+        ui.label("");
+
+        for achievement in rules::ALL {
+            let name = RichText::new(achievement.get_name()).strong();
+            if unlocked.contains(&achievement) {
+                ui.label(name.color(if app.args.difficult {
+                    colors::GREEN
+                } else {
+                    colors::DARK_MODE_GREEN
+                }));
+            } else {
+                ui.label(name.weak());
+            }
+            ui.label(achievement.get_description());
+        }
+    });
+}
+
+/// Notification of achievements just unlocked, which stays until dismissed
+pub fn notification(ctx: &egui::Context, unlocked: &mut Vec<Achievement>) {
+    if unlocked.is_empty() {
+        return;
+    }
+    Window::new("Achievement unlocked!")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_TOP, [0.0, metrics::HEADER_HEIGHT])
+        .show(ctx, |ui| {
+            for achievement in unlocked.iter() {
+                ui.label(RichText::new(achievement.get_name()).strong());
+                ui.label(achievement.get_description());
+            }
+            if ui.button("OK").clicked() {
+                unlocked.clear();
+            }
+        });
+}
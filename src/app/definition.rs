@@ -1,31 +1,83 @@
-use egui::ScrollArea;
+use egui::{CollapsingHeader, RichText, ScrollArea};
 
-use crate::dict::DICT;
 use crate::game::GameStatus;
 
 use super::{metrics, WordleApp};
 
-/// Definition panel
-pub fn definition(ui: &mut egui::Ui, app: &WordleApp) {
-    ui.collapsing("Definition", |ui| {
+/// Definition panel, of the guessed word clicked or the answer when the game is over
+pub fn definition(ui: &mut egui::Ui, app: &mut WordleApp) {
+    // Open the panel once a guessed word is clicked, and leave it to the player afterwards
+    let open = app.open_definition.then_some(true);
+    app.open_definition = false;
+
+    CollapsingHeader::new("Definition").open(open).show(ui, |ui| {
         ui.set_max_width(metrics::PANEL_WIDTH);
         ui.set_max_height(metrics::PANEL_HEIGHT);
-        let answer = match app.game_status.as_ref().unwrap() {
+        let word = match (&app.defined, app.game_status.as_ref().unwrap()) {
+            (Some(word), _) => word,
             // Show help message when the game is going
-            GameStatus::Going => {
+            (None, GameStatus::Going) => {
                 ui.label("Come back later when the game is over!");
+                ui.label("Click on a guessed word to look it up.");
                 ui.label("If you need some help, try out typing 'HINT'!");
                 return;
             }
-            GameStatus::Won(_) => &app.game.as_ref().unwrap().get_guesses().last().unwrap().0,
-            GameStatus::Failed(ref answer) => answer,
+            (None, GameStatus::Won(_)) => {
+                &app.game.as_ref().unwrap().get_guesses().last().unwrap().0
+            }
+            (None, GameStatus::Failed(ref answer)) => answer,
         };
 
+        let definition = app
+            .metadata
+            .get(word)
+            .and_then(|entry| entry.definition.as_deref());
+        let entry = app.dictionary.get(word);
+
+        ui.label(RichText::new(word).strong());
+        if definition.is_none() && entry.is_none_or(|entry| entry.senses.is_empty()) {
+            ui.label("No definition found.");
+            return;
+        }
+
         // Show the definition
         ScrollArea::vertical().show(ui, |ui| {
-            // ui.set_max_height(50.0);
-            for (i, sense) in DICT.get(answer).unwrap().iter().enumerate() {
-                ui.label(format!("{}: {sense}", i + 1));
+            if let Some(pronunciation) = entry.and_then(|entry| entry.pronunciation) {
+                ui.label(RichText::new(format!("/{pronunciation}/")).weak());
+            }
+
+            // The definition from the word list comes before those from the dictionary
+            let mut number = 0;
+            if let Some(definition) = definition {
+                number += 1;
+                ui.label(format!("{number}: {definition}"));
+            }
+            for sense in entry.map_or(&[][..], |entry| entry.senses) {
+                number += 1;
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(format!("{number}:"));
+                    if let Some(part_of_speech) = sense.part_of_speech {
+                        ui.label(RichText::new(part_of_speech).italics());
+                    }
+                    ui.label(sense.definition);
+                });
+                for example in sense.examples {
+                    ui.label(RichText::new(format!("\"{example}\"")).italics().weak());
+                }
+                if !sense.synonyms.is_empty() {
+                    ui.horizontal_wrapped(|ui| {
+                        ui.label(RichText::new("Synonyms:").strong());
+                        ui.label(sense.synonyms.join(", "));
+                    });
+                }
+            }
+
+            if let Some(etymology) = entry.and_then(|entry| entry.etymology) {
+                ui.label("");
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new("Origin:").strong());
+                    ui.label(etymology);
+                });
             }
         });
     });
@@ -1,21 +1,31 @@
-use crate::game::{Game, LetterStatus};
+use egui::CursorIcon;
+
+use crate::game::{GuessStatus, LetterStatus};
+use crate::language;
 
 use super::{
     letter::{letter, Letter},
     metrics,
 };
 
-/// Render the letter grid
-pub fn grid(ui: &mut egui::Ui, game: &Game, guess: &mut String, difficult: bool) {
+/// Render the letter grid, and return the guessed word clicked if any
+pub fn grid(
+    ui: &mut egui::Ui,
+    guesses: &[(String, GuessStatus)],
+    guess: &str,
+    difficult: bool,
+) -> Option<String> {
+    let mut clicked = None;
+
     // The letter grid
     for i in 0..metrics::ROWS as usize {
         for j in 0..metrics::COLUMNS as usize {
             // Already guessed
-            if i < game.get_round() {
-                let guess = &game.get_guesses()[i];
-                let letter_char = Some(guess.0.chars().nth(j).unwrap().to_ascii_uppercase());
+            if i < guesses.len() {
+                let guess = &guesses[i];
+                let letter_char = language::letters(&guess.0).nth(j);
                 let status = guess.1[j];
-                letter(
+                let response = letter(
                     ui,
                     difficult,
                     i as i32,
@@ -25,7 +35,11 @@ pub fn grid(ui: &mut egui::Ui, game: &Game, guess: &mut String, difficult: bool)
                         status,
                     },
                 );
-            } else if i == game.get_round() && j < guess.len() {
+                // Guessed rows can be clicked to show their definitions
+                if response.on_hover_cursor(CursorIcon::PointingHand).clicked() {
+                    clicked = Some(guess.0.clone());
+                }
+            } else if i == guesses.len() && j < language::count_letters(guess) {
                 // We'll input words in this row, and the jth letter already input
                 letter(
                     ui,
@@ -33,10 +47,10 @@ pub fn grid(ui: &mut egui::Ui, game: &Game, guess: &mut String, difficult: bool)
                     i as i32,
                     j as i32,
                     &Letter {
-                        letter: guess.chars().nth(j),
+                        letter: language::letters(guess).nth(j),
                         status: LetterStatus::Unknown,
                     },
-                )
+                );
             } else {
                 // Blank letter
                 letter(
@@ -52,4 +66,6 @@ pub fn grid(ui: &mut egui::Ui, game: &Game, guess: &mut String, difficult: bool)
             }
         }
     }
+
+    clicked
 }
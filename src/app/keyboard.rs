@@ -1,11 +1,14 @@
-use egui::{Align2, Color32, CursorIcon, FontFamily, Key, Modifiers, Pos2, Rect, Sense, Vec2};
+use egui::{
+    Align2, Color32, CursorIcon, Event, FontFamily, Key, Modifiers, Pos2, Rect, Sense, Vec2,
+};
 
-use crate::game::{self, Alphabet, GameStatus, LetterStatus};
+use crate::game::{Alphabet, GameStatus, LetterStatus};
+use crate::language::{self, Language};
 
 use super::{colors, metrics, utils};
 
-pub const ENTER: char = '\n';
-pub const BACKSPACE: char = '\x08';
+pub const ENTER: &str = "\n";
+pub const BACKSPACE: &str = "\x08";
 
 /// How many width does the keyboard expects to occupy
 fn get_expected_width() -> f32 {
@@ -23,10 +26,11 @@ pub fn get_keyboard_size_factor(ui: &egui::Ui) -> f32 {
     let expected = get_expected_width();
     let available = get_available_width(ui);
     // Scale the letter grid to fit in available space
-    return (available / expected).min(1.0);
+    (available / expected).min(1.0)
 }
 
-fn get_key_fill_color(dark: bool, status: &LetterStatus) -> Color32 {
+/// Color of a key, which is also the color of a letter in this status elsewhere
+pub fn get_key_fill_color(dark: bool, status: &LetterStatus) -> Color32 {
     if dark {
         match status {
             LetterStatus::Unknown => colors::DARK_MODE_GRAY,
@@ -152,10 +156,11 @@ fn get_backspace_key_text_color(dark: bool, status: &GameStatus) -> Color32 {
 fn letter_key(
     ui: &mut egui::Ui,
     dark: bool,
-    c: char,
+    c: &str,
     status: &LetterStatus,
     x: f32,
     y: f32,
+    width: f32,
 ) -> bool {
     let factor = get_keyboard_size_factor(ui);
 
@@ -163,7 +168,7 @@ fn letter_key(
     let rect = Rect::from_min_size(
         Pos2 { x, y },
         Vec2 {
-            x: metrics::KEY_WIDTH * factor,
+            x: width,
             y: metrics::KEY_HEIGHT * factor,
         },
     );
@@ -210,6 +215,7 @@ fn letter_key(
 }
 
 /// Enter key, meanwhile shows a message when game over
+#[allow(clippy::too_many_arguments)]
 fn enter_key(
     ui: &mut egui::Ui,
     dark: bool,
@@ -218,6 +224,7 @@ fn enter_key(
     hint: bool,
     x: f32,
     y: f32,
+    width: f32,
 ) -> bool {
     let factor = get_keyboard_size_factor(ui);
 
@@ -225,7 +232,7 @@ fn enter_key(
     let rect = Rect::from_min_size(
         Pos2 { x, y },
         Vec2 {
-            x: metrics::KEY_WIDTH_LARGE * factor,
+            x: width,
             y: metrics::KEY_HEIGHT * factor,
         },
     );
@@ -298,14 +305,21 @@ fn enter_key(
 }
 
 /// Backspace key, meanwhile shows a message when game over
-fn backspace_key(ui: &mut egui::Ui, dark: bool, status: &GameStatus, x: f32, y: f32) -> bool {
+fn backspace_key(
+    ui: &mut egui::Ui,
+    dark: bool,
+    status: &GameStatus,
+    x: f32,
+    y: f32,
+    width: f32,
+) -> bool {
     let factor = get_keyboard_size_factor(ui);
 
     // Widget rect
     let rect = Rect::from_min_size(
         Pos2 { x, y },
         Vec2 {
-            x: metrics::KEY_WIDTH_LARGE * factor,
+            x: width,
             y: metrics::KEY_HEIGHT * factor,
         },
     );
@@ -360,119 +374,94 @@ fn backspace_key(ui: &mut egui::Ui, dark: bool, status: &GameStatus, x: f32, y:
     response.clicked()
 }
 
-/// The keyboard widget
-/// Returns which key is pressed
+/// The keyboard widget, in the layout of a language
+/// Returns which key is pressed, a letter or one of `ENTER` and `BACKSPACE`
 /// Params valid: Is this guess valid (if so, the enter button is enabled)
 /// Prams hint: Whether to show hint button
 pub fn keyboard(
     ui: &mut egui::Ui,
     dark: bool,
     alphabet: &Alphabet,
+    language: &Language,
     status: &GameStatus,
     valid: bool,
     hint: bool,
-) -> Option<char> {
-    let mut pressed: Option<char> = None;
+) -> Option<String> {
+    let mut pressed: Option<String> = None;
 
     let factor = get_keyboard_size_factor(ui);
 
     // Render the r-th row
     let mut row = |r, letters: &str| {
-        let mut row_width = metrics::PADDING * 2.0
-            + letters.len() as f32 * (metrics::KEY_WIDTH + metrics::KEY_H_MARGIN) * factor
-            - metrics::KEY_H_MARGIN * factor;
-        if letters.contains(ENTER) {
-            row_width += (metrics::KEY_WIDTH_LARGE - metrics::KEY_WIDTH) * factor;
-        }
-        if letters.contains(BACKSPACE) {
-            row_width += (metrics::KEY_WIDTH_LARGE - metrics::KEY_WIDTH) * factor;
-        }
+        let get_key_width = |c| {
+            if c == ENTER || c == BACKSPACE {
+                metrics::KEY_WIDTH_LARGE
+            } else {
+                metrics::KEY_WIDTH
+            }
+        };
+        let keys = language::count_letters(letters) as f32;
+        let keys_width: f32 = language::letters(letters).map(get_key_width).sum();
+        // Keys of rows longer than the QWERTY ones are narrowed to fit in
+        let expected_width = get_expected_width() - metrics::PADDING * 2.0;
+        let squeeze = ((expected_width - (keys - 1.0) * metrics::KEY_H_MARGIN)
+            / keys_width)
+            .min(1.0);
+
+        let row_width = metrics::PADDING * 2.0
+            + (keys_width * squeeze + (keys - 1.0) * metrics::KEY_H_MARGIN) * factor;
         let mut allocated_width = metrics::PADDING;
-        for c in letters.chars() {
+        for c in language::letters(letters) {
+            let width = get_key_width(c) * squeeze * factor;
             let x = {
                 let x = utils::get_screen_width(ui) / 2.0 - row_width / 2.0 + allocated_width;
-                allocated_width += metrics::KEY_H_MARGIN * factor
-                    + if c == ENTER {
-                        metrics::KEY_WIDTH_LARGE
-                    } else {
-                        metrics::KEY_WIDTH
-                    } * factor;
+                allocated_width += metrics::KEY_H_MARGIN * factor + width;
                 x
             };
             let y = utils::get_screen_height(ui)
                 - (metrics::KEY_HEIGHT + metrics::KEY_V_MARGIN) * (3.0 + 1.0 - r as f32) * factor;
             // Detect keystroke
-            match c {
-                ENTER => {
-                    if enter_key(ui, dark, status, valid, hint, x, y) {
-                        pressed = Some(c)
-                    }
-                }
-                BACKSPACE => {
-                    if backspace_key(ui, dark, status, x, y) {
-                        pressed = Some(c)
-                    }
-                }
+            let clicked = match c {
+                ENTER => enter_key(ui, dark, status, valid, hint, x, y, width),
+                BACKSPACE => backspace_key(ui, dark, status, x, y, width),
+                // Letters on the keyboard are in the alphabet
                 _ => {
-                    if letter_key(ui, dark, c, &alphabet[game::get_index(c)], x, y) {
-                        pressed = Some(c);
-                    }
+                    let status = &alphabet[language.get_index(c).unwrap()];
+                    letter_key(ui, dark, c, status, x, y, width)
                 }
+            };
+            if clicked {
+                pressed = Some(c.to_string());
             }
         }
     };
 
-    row(1, "QWERTYUIOP");
-    row(2, "ASDFGHJKL");
-    row(3, &format!("{ENTER}ZXCVBNM{BACKSPACE}"));
+    // Enter and backspace keys are at both ends of the last row
+    let rows = language.keyboard.len();
+    for (r, letters) in language.keyboard.iter().enumerate() {
+        if r + 1 == rows {
+            row(r + 1, &format!("{ENTER}{letters}{BACKSPACE}"));
+        } else {
+            row(r + 1, letters);
+        }
+    }
 
     // Track physical keyboard input
-    const TRACKED_KEYS: [egui::Key; 28] = [
-        Key::A,
-        Key::B,
-        Key::C,
-        Key::D,
-        Key::E,
-        Key::F,
-        Key::G,
-        Key::H,
-        Key::I,
-        Key::J,
-        Key::K,
-        Key::L,
-        Key::M,
-        Key::N,
-        Key::O,
-        Key::P,
-        Key::Q,
-        Key::R,
-        Key::S,
-        Key::T,
-        Key::U,
-        Key::V,
-        Key::W,
-        Key::X,
-        Key::Y,
-        Key::Z,
-        Key::Enter,
-        Key::Backspace,
-    ];
-
-    for key in TRACKED_KEYS {
-        // Early return to avoid key conflict
-        if ui.input_mut().consume_key(Modifiers::NONE, key) {
-            pressed = match key {
-                Key::Enter => Some(ENTER),
-                Key::Backspace => Some(BACKSPACE),
-                _ => format!("{key:?}").chars().nth(0),
+    for key in [Key::Enter, Key::Backspace] {
+        // Shift is allowed (though not required)
+        for modifiers in [Modifiers::NONE, Modifiers::SHIFT] {
+            if ui.input_mut().consume_key(modifiers, key) {
+                pressed = Some(if key == Key::Enter { ENTER } else { BACKSPACE }.to_string());
             }
         }
-        // Shift is allowed (though not required)
-        if ui.input_mut().consume_key(Modifiers::SHIFT, key) {
-            pressed = match key {
-                Key::Enter => Some(ENTER),
-                Key::Backspace => Some(BACKSPACE),
-                _ => format!("{key:?}").chars().nth(0),
+    }
+
+    // Letters are typed as text, as keys are only known for latin letters
+    for event in &ui.input().events {
+        if let Event::Text(text) = event {
+            let text = language.to_upper(text);
+            if language.get_index(&text).is_some() {
+                pressed = Some(text);
             }
         }
     }
@@ -7,18 +7,18 @@ use super::keyboard;
 use super::metrics;
 use super::utils;
 
-/// A letter consists of a char and a status, for rendering in UI
+/// A letter consists of a grapheme and a status, for rendering in UI
 /// The letter could be None, standing for a state of "not yet input"
-pub struct Letter {
-    pub letter: Option<char>,
+pub struct Letter<'a> {
+    pub letter: Option<&'a str>,
     pub status: LetterStatus,
 }
 
-impl Letter {
+impl Letter<'_> {
     /// Get the stroke color for a letter
     fn get_stroke_color(&self, dark: bool) -> Color32 {
         if dark {
-            if let Some(_) = self.letter {
+            if self.letter.is_some() {
                 match self.status {
                     LetterStatus::Unknown => colors::DARK_MODE_GRAY,
                     LetterStatus::Red => colors::DARK_MODE_DARK_GRAY,
@@ -29,7 +29,7 @@ impl Letter {
                 colors::DARK_MODE_DARK_GRAY
             }
         } else {
-            if let Some(_) = self.letter {
+            if self.letter.is_some() {
                 match self.status {
                     LetterStatus::Unknown => colors::DARK_GRAY,
                     LetterStatus::Red => colors::DARK_GRAY,
@@ -45,7 +45,7 @@ impl Letter {
     /// Get the fill color for a letter
     fn get_fill_color(&self, dark: bool) -> Color32 {
         if dark {
-            if let Some(_) = self.letter {
+            if self.letter.is_some() {
                 match self.status {
                     LetterStatus::Unknown => colors::DARK_MODE_BLACK,
                     LetterStatus::Red => colors::DARK_MODE_DARK_GRAY,
@@ -56,7 +56,7 @@ impl Letter {
                 colors::DARK_MODE_BLACK
             }
         } else {
-            if let Some(_) = self.letter {
+            if self.letter.is_some() {
                 match self.status {
                     LetterStatus::Unknown => colors::WHITE,
                     LetterStatus::Red => colors::DARK_GRAY,
@@ -116,7 +116,13 @@ fn get_letter_size_factor(ui: &egui::Ui) -> f32 {
 }
 
 /// The letter widget
-pub fn letter(ui: &mut egui::Ui, dark: bool, row: i32, column: i32, letter: &Letter) {
+pub fn letter(
+    ui: &mut egui::Ui,
+    dark: bool,
+    row: i32,
+    column: i32,
+    letter: &Letter,
+) -> egui::Response {
     // Assert parameters row and column are in correct range
     assert!((0..metrics::ROWS).contains(&row));
     assert!((0..metrics::COLUMNS).contains(&column));
@@ -141,15 +147,15 @@ pub fn letter(ui: &mut egui::Ui, dark: bool, row: i32, column: i32, letter: &Let
 
     // Painting rect
     let rect = Rect::from_min_size(
-        Pos2 { x, y: y as f32 },
+        Pos2 { x, y },
         Vec2 {
             x: box_size,
             y: box_size,
         },
     );
 
-    // Allocate space for the widget
-    ui.allocate_rect(rect, Sense::hover());
+    // Allocate space for the widget, and sense click event on guessed letters
+    let response = ui.allocate_rect(rect, Sense::click());
 
     // Compute animated colors
     let fill_color = utils::animate_color(
@@ -180,11 +186,13 @@ pub fn letter(ui: &mut egui::Ui, dark: bool, row: i32, column: i32, letter: &Let
     ui.painter().text(
         rect.center(),
         Align2::CENTER_CENTER,
-        letter.letter.unwrap_or(' '),
+        letter.letter.unwrap_or(" "),
         egui::FontId {
             size: font_size,
             family: FontFamily::Name("SF".into()),
         },
         text_color,
     );
+
+    response
 }
@@ -0,0 +1,180 @@
+use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver};
+use std::time::Duration;
+
+use egui::{CollapsingHeader, RichText};
+
+use crate::game::WORD_LENGTH;
+use crate::language::{self, Language};
+use crate::race::{self as host, Board, Request, Response};
+
+use super::{grid, keyboard, metrics};
+
+/// How often to look for messages of the host, which come without any input
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A race joined, where guesses are checked by the host instead of a game of our own
+pub struct Race {
+    stream: TcpStream,
+    receiver: Receiver<Option<Response>>,
+    board: Board,
+    guess: String,
+    /// Whether the host has gone
+    closed: bool,
+}
+
+impl Race {
+    /// Connect to a host and join its race
+    pub fn join(
+        address: &str,
+        name: Option<String>,
+        language: &'static Language,
+    ) -> Result<Self, String> {
+        let (sender, receiver) = mpsc::channel();
+        let stream = host::connect(address, Request::Join { name }, sender, |response| response)?;
+        Ok(Self {
+            stream,
+            receiver,
+            board: Board::new(language),
+            guess: String::new(),
+            closed: false,
+        })
+    }
+
+    /// Learn from the messages of the host received so far
+    fn receive(&mut self) {
+        while let Ok(response) = self.receiver.try_recv() {
+            match response {
+                Some(response) => self.board.apply(&response),
+                None => self.closed = true,
+            }
+        }
+    }
+
+    /// Whether guesses can be made now
+    fn is_playing(&self) -> bool {
+        self.board.started && !self.board.is_over() && !self.closed
+    }
+}
+
+/// Race panel, with opponents and how the race goes
+pub fn panel(ui: &mut egui::Ui, race: &Race, dark: bool) {
+    CollapsingHeader::new("Race  ")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.set_max_width(metrics::PANEL_WIDTH);
+            let board = &race.board;
+
+            if let Some(ref standings) = board.standings {
+                match board.get_winner() {
+                    Some(winner) => ui.label(RichText::new(format!("{winner} wins!")).strong()),
+                    None => ui.label(RichText::new("Nobody wins.").strong()),
+                };
+                for (i, standing) in standings.iter().enumerate() {
+                    ui.label(format!(
+                        "{}. {}: {}",
+                        i + 1,
+                        standing.player,
+                        standing.describe()
+                    ));
+                }
+                return;
+            }
+            if race.closed {
+                ui.label("The host has gone.");
+                return;
+            }
+            if !board.started {
+                ui.label(format!(
+                    "Waiting for players ({}/{})",
+                    board.lobby.len(),
+                    board.needed
+                ));
+                for player in &board.lobby {
+                    ui.label(player);
+                }
+                return;
+            }
+
+            // Opponents are shown by the patterns of their guesses
+            for opponent in &board.opponents {
+                if opponent.left {
+                    ui.label(RichText::new(format!("{} (left)", opponent.name)).weak());
+                } else {
+                    ui.label(RichText::new(&opponent.name).strong());
+                }
+                for row in &opponent.rows {
+                    ui.horizontal(|ui| {
+                        for status in row {
+                            ui.label(
+                                RichText::new("■")
+                                    .color(keyboard::get_key_fill_color(dark, status)),
+                            );
+                        }
+                    });
+                }
+            }
+
+            if let Some((won, ref answer)) = board.over {
+                ui.label("");
+                if won {
+                    ui.label(format!("You won in {} guesses!", board.guesses.len()));
+                } else {
+                    ui.label(format!("The answer is: {answer}"));
+                }
+                ui.label("Waiting for other players...");
+            }
+            if let Some(ref error) = board.error {
+                ui.label("");
+                ui.label(RichText::new(error).strong());
+            }
+        });
+}
+
+/// The letter grid and the keyboard of a race, taking guesses to send to the host
+pub fn race(ui: &mut egui::Ui, race: &mut Race, dark: bool) {
+    race.receive();
+    ui.ctx().request_repaint_after(POLL_INTERVAL);
+
+    grid(ui, &race.board.guesses, &race.guess, dark);
+
+    let playing = race.is_playing();
+    let valid = playing && language::count_letters(&race.guess) == WORD_LENGTH;
+    let status = race.board.get_game_status();
+    let Some(key) = keyboard(
+        ui,
+        dark,
+        &race.board.alphabet,
+        race.board.language,
+        &status,
+        valid,
+        false,
+    ) else {
+        return;
+    };
+    // Keys are of no use before the race or after the game
+    if !playing {
+        return;
+    }
+    match key.as_str() {
+        // The host tells whether the guess is taken
+        keyboard::ENTER => {
+            if valid {
+                let word = std::mem::take(&mut race.guess);
+                host::send(&mut race.stream, &Request::Guess { word });
+            }
+        }
+        keyboard::BACKSPACE => {
+            // Remove the last letter, which may be of several chars
+            if let Some(last) = language::letters(&race.guess).last() {
+                race.guess.truncate(race.guess.len() - last.len());
+            }
+        }
+        // Enter a letter
+        _ => {
+            if language::count_letters(&race.guess) < WORD_LENGTH {
+                race.guess.push_str(&key);
+            }
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use egui::{CollapsingHeader, ComboBox, RichText};
+
+use crate::game::GuessStatus;
+use crate::race as host;
+use crate::replay::{Replay, Step};
+
+use super::{grid, metrics};
+
+/// A race played back step by step, with the guesses of a player at a time
+pub struct Playback {
+    replay: Replay,
+    /// How many steps are shown
+    step: usize,
+    /// The player whose guesses are shown
+    player: usize,
+}
+
+impl Playback {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            step: 0,
+            player: 0,
+        }
+    }
+
+    /// Guesses of the player chosen, up to the step shown
+    fn get_guesses(&self) -> Vec<(String, GuessStatus)> {
+        let Some(player) = self.replay.race.players.get(self.player) else {
+            return vec![];
+        };
+        self.replay.steps[..self.step]
+            .iter()
+            .filter_map(|entry| match entry.step {
+                Step::Guess {
+                    player: ref guesser,
+                    ref word,
+                    ref status,
+                } if guesser == player => Some((word.clone(), host::get_status(status))),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Describe a step of a race
+fn describe(step: &Step) -> String {
+    match step {
+        Step::Guess { player, word, .. } => format!("{player} guessed {word}"),
+        Step::GameOver {
+            player,
+            won: true,
+            rounds,
+        } => format!("{player} won in {rounds} guesses!"),
+        Step::GameOver { player, .. } => format!("{player} failed"),
+        Step::Left { player } => format!("{player} left the race"),
+        Step::RaceOver {
+            answer,
+            winner: Some(winner),
+        } => format!("The answer is {answer}. {winner} wins!"),
+        Step::RaceOver { answer, .. } => format!("The answer is {answer}. Nobody wins."),
+    }
+}
+
+/// Replay panel, to step through a race and choose whose guesses to show
+pub fn panel(ui: &mut egui::Ui, playback: &mut Playback) {
+    CollapsingHeader::new("Replay  ")
+        .default_open(true)
+        .show(ui, |ui| {
+            ui.set_max_width(metrics::PANEL_WIDTH);
+            let race = &playback.replay.race;
+            ui.label(RichText::new(format!("Race of {}", race.players.join(", "))).strong());
+
+            let players = &race.players;
+            ComboBox::from_label("Player")
+                .selected_text(players.get(playback.player).map_or("", String::as_str))
+                .show_ui(ui, |ui| {
+                    for (i, player) in players.iter().enumerate() {
+                        ui.selectable_value(&mut playback.player, i, player);
+                    }
+                });
+
+            let steps = playback.replay.steps.len();
+            ui.horizontal(|ui| {
+                if ui
+                    .add_enabled(playback.step > 0, egui::Button::new("Prev"))
+                    .clicked()
+                {
+                    playback.step -= 1;
+                }
+                if ui
+                    .add_enabled(playback.step < steps, egui::Button::new("Next"))
+                    .clicked()
+                {
+                    playback.step += 1;
+                }
+                ui.label(format!("{}/{steps}", playback.step));
+            });
+
+            // The last step shown
+            if let Some(entry) = playback
+                .step
+                .checked_sub(1)
+                .map(|i| &playback.replay.steps[i])
+            {
+                ui.label(format!(
+                    "{:.1}s: {}",
+                    entry.time_ms as f64 / 1000.0,
+                    describe(&entry.step)
+                ));
+            }
+        });
+}
+
+/// The letter grid of the player chosen, as of the step shown
+pub fn replay(ui: &mut egui::Ui, playback: &Playback, dark: bool) {
+    grid(ui, &playback.get_guesses(), "", dark);
+}
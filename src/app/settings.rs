@@ -1,4 +1,7 @@
-use egui::{DragValue, Label};
+use egui::{ComboBox, DragValue, Label};
+
+use crate::builtin_languages;
+use crate::packs;
 
 use super::{metrics, WordleApp};
 
@@ -15,19 +18,80 @@ pub fn settings(ui: &mut egui::Ui, app: &mut WordleApp) {
         }
         ui.add(Label::new("Any revealed hints must be used in subsequent guesses.").wrap(true));
 
+        // Accents
+        if ui.checkbox(&mut app.args.ignore_accents, "Ignore Accents").changed() {
+            game.set_ignore_accents(app.args.ignore_accents);
+        }
+        ui.add(Label::new("Accented letters count as their base letters, like Á as A.").wrap(true));
+
         // Random seed
         ui.horizontal(|ui| {
             ui.label("Seed: ");
 
-            let seed_before = app.args.seed.unwrap();
-
+            // The answer list is ordered by the new seed when the next game starts
             ui.add(DragValue::new(app.args.seed.as_mut().unwrap()));
+        });
+
+        // Puzzle pack
+        ui.horizontal(|ui| {
+            ui.label("Pack: ");
+
+            let pack_before = app.args.pack.clone();
+
+            // Only packs in the language chosen can be played
+            let language = app.args.get_language();
+            let packs: Vec<_> = packs::builtin()
+                .into_iter()
+                .filter(|pack| pack.check_language(language).is_ok())
+                .collect();
+            let title = |name: &Option<String>| match name {
+                Some(name) => packs
+                    .iter()
+                    .find(|pack| &pack.name == name)
+                    .map_or(name.clone(), |pack| pack.title.clone()),
+                None => "Classic".to_string(),
+            };
+            ComboBox::from_id_source("pack")
+                .selected_text(title(&app.args.pack))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut app.args.pack, None, "Classic");
+                    for pack in &packs {
+                        ui.selectable_value(
+                            &mut app.args.pack,
+                            Some(pack.name.clone()),
+                            &pack.title,
+                        )
+                        .on_hover_text(&pack.description);
+                    }
+                });
+
+            // If pack is changed, start from the first day, with the word lists of the pack
+            if app.args.pack != pack_before {
+                app.args.day = Some(0);
+            }
+        });
+
+        // Language
+        ui.horizontal(|ui| {
+            ui.label("Language: ");
+
+            let language_before = app.args.language.clone();
 
-            let seed_after = app.args.seed.unwrap();
+            ComboBox::from_id_source("language")
+                .selected_text(app.args.get_language().title)
+                .show_ui(ui, |ui| {
+                    for language in builtin_languages::LANGUAGES {
+                        ui.selectable_value(
+                            &mut app.args.language,
+                            Some(language.name.to_string()),
+                            language.title,
+                        );
+                    }
+                });
 
-            // If seed is changed, shuffle the answer list again
-            if seed_after != seed_before {
-                app.shuffle_answer_list(seed_after);
+            // If language is changed, start from the first day, with the word lists of the language
+            if app.args.language != language_before {
+                app.args.day = Some(0);
             }
         });
 
@@ -45,7 +109,7 @@ pub fn settings(ui: &mut egui::Ui, app: &mut WordleApp) {
         });
 
         ui.add(
-            Label::new("The above two settings won't go into effect until next game.").wrap(true),
+            Label::new("The above settings won't go into effect until next game.").wrap(true),
         );
 
         if ui.button("Go").clicked() {
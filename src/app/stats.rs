@@ -1,41 +1,279 @@
-use egui::RichText;
+#[cfg(target_arch = "wasm32")]
+use std::collections::HashMap;
 
-use crate::stats::Stats;
+use chrono::NaiveDate;
+use egui::{ComboBox, DragValue, RichText, TextEdit};
 
-use super::{colors, metrics};
+use crate::analytics::{self, Analytics};
+use crate::stats::{self, Filter, Game, Stats};
+
+use super::{colors, metrics, WordleApp};
+
+/// Tabs of the statistics panel
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Tab {
+    #[default]
+    Overview,
+    Openings,
+    Hardest,
+    Letters,
+}
+
+/// Controls of the statistics panel
+pub struct Controls {
+    tab: Tab,
+    top: usize,
+    difficult: Option<bool>,
+    since: String,
+    until: String,
+    word_list: Option<String>,
+}
+
+impl Default for Controls {
+    fn default() -> Self {
+        Self {
+            tab: Tab::default(),
+            top: stats::DEFAULT_TOP,
+            difficult: None,
+            since: String::new(),
+            until: String::new(),
+            word_list: None,
+        }
+    }
+}
+
+impl Controls {
+    /// Get the filter on games. Dates which can't be parsed are ignored
+    fn get_filter(&self) -> Filter {
+        let parse_date = |date: &str| NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").ok();
+        Filter {
+            since: parse_date(&self.since),
+            until: parse_date(&self.until),
+            difficult: self.difficult,
+            word_list: self.word_list.clone(),
+        }
+    }
+}
 
 /// Statistics panel
-pub fn stats(ui: &mut egui::Ui, dark: bool, stats: &Stats) {
+pub fn stats(ui: &mut egui::Ui, app: &mut WordleApp) {
     ui.collapsing("Statistics", |ui| {
         ui.set_min_width(metrics::PANEL_WIDTH);
+        ui.set_max_width(metrics::PANEL_WIDTH);
+
+        let controls = &mut app.stats_controls;
 
-        ui.label(
-            RichText::new(format!("Wins:  {}", stats.get_wins()))
-                .strong()
-                .color(if dark {
-                    colors::GREEN
-                } else {
-                    colors::DARK_MODE_GREEN
-                }),
-        );
-        ui.label(
-            RichText::new(format!("Fails: {}", stats.get_fails()))
-                .strong()
-                .color(if dark {
-                    colors::YELLOW
-                } else {
-                    colors::DARK_MODE_YELLOW
-                }),
-        );
-        ui.label(
-            RichText::new(format!("Average tries: {:.2}", stats.get_average_tries())).strong(),
-        );
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut controls.tab, Tab::Overview, "All");
+            ui.selectable_value(&mut controls.tab, Tab::Openings, "Open");
+            ui.selectable_value(&mut controls.tab, Tab::Hardest, "Hard");
+            ui.selectable_value(&mut controls.tab, Tab::Letters, "Abc");
+        });
+
+        // Analytics are outdated if any filter is changed
+        if filters(ui, controls, app.stats.get_games()) {
+            app.analytics = None;
+        }
 
         ui.label("");
 
-        ui.label(RichText::new(format!("Favorite words:")).strong());
-        for (word, times) in stats.get_favorite_words() {
-            ui.label(format!("{word}: used {times} times"));
+        let filtered = app.stats.filtered(&controls.get_filter());
+        let stats = filtered.as_ref().unwrap_or(&app.stats);
+
+        if controls.tab == Tab::Overview {
+            overview(ui, app.args.difficult, stats, controls.top);
+            return;
+        }
+
+        // Analytics are expensive, so only compute them again after a game is over
+        let analytics = app.analytics.get_or_insert_with(|| {
+            let games = stats.get_games();
+            // Word lists can't be read from files on the web
+            #[cfg(not(target_arch = "wasm32"))]
+            let mut answer_lists = analytics::load_answer_lists(
+                games,
+                app.args.get_language(),
+                app.args.pack_dir.as_deref(),
+            );
+            #[cfg(target_arch = "wasm32")]
+            let mut answer_lists = HashMap::new();
+            // The list in use is known even if it can't be loaded again
+            answer_lists.insert(app.args.get_word_list_name(), app.answer_list.clone());
+            Analytics::new(
+                games,
+                &answer_lists,
+                &app.answer_list,
+                &app.metadata,
+                controls.top,
+            )
+        });
+
+        match controls.tab {
+            Tab::Overview => (),
+            Tab::Openings => {
+                ui.label(RichText::new("Opening words:").strong());
+                for opening in &analytics.openings {
+                    ui.label(format!(
+                        "{}: {} games, {} won, {:.2} tries",
+                        opening.word,
+                        opening.games,
+                        opening.wins,
+                        opening.get_average_tries()
+                    ));
+                }
+            }
+            Tab::Hardest => {
+                ui.label(RichText::new("Hardest answers:").strong());
+                for hardest in &analytics.hardest {
+                    match hardest.tries {
+                        Some(tries) => ui.label(format!("{}: {tries} tries", hardest.answer)),
+                        None => ui.label(format!("{}: failed", hardest.answer)),
+                    };
+                }
+
+                // Tags are only known with word lists with metadata
+                if !analytics.tags.is_empty() {
+                    ui.label("");
+                    ui.label(RichText::new("Answer tags:").strong());
+                    for tag in &analytics.tags {
+                        ui.label(format!(
+                            "{}: {} games, {} won, {:.2} tries",
+                            tag.tag,
+                            tag.games,
+                            tag.wins,
+                            tag.get_average_tries()
+                        ));
+                    }
+                }
+            }
+            Tab::Letters => {
+                ui.label(RichText::new("Most missed letters:").strong());
+                for (letter, count) in &analytics.missed_letters {
+                    ui.label(format!("{letter}: missed {count} times"));
+                }
+
+                ui.label("");
+
+                ui.label(RichText::new("Remaining candidates:").strong());
+                for (round, remaining) in analytics.remaining.iter().enumerate() {
+                    if let Some(remaining) = remaining {
+                        ui.label(format!("After guess {}: {remaining:.2}", round + 1));
+                    }
+                }
+                if analytics.skipped > 0 {
+                    ui.label(
+                        RichText::new(format!(
+                            "{} games left out, as their word lists could not be loaded",
+                            analytics.skipped
+                        ))
+                        .color(colors::YELLOW),
+                    );
+                }
+            }
         }
     });
 }
+
+/// Filters on games counted in statistics. Return whether any filter is changed
+fn filters(ui: &mut egui::Ui, controls: &mut Controls, games: &[Game]) -> bool {
+    let mut changed = false;
+    ui.collapsing("Filters", |ui| {
+        ui.horizontal(|ui| {
+            ui.label("Top:   ");
+            changed |= ui
+                .add(DragValue::new(&mut controls.top).clamp_range(1..=20))
+                .changed();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Mode:  ");
+            ComboBox::from_id_source("stats_mode")
+                .selected_text(match controls.difficult {
+                    None => "All",
+                    Some(false) => "Normal",
+                    Some(true) => "Hard",
+                })
+                .show_ui(ui, |ui| {
+                    for (difficult, text) in
+                        [(None, "All"), (Some(false), "Normal"), (Some(true), "Hard")]
+                    {
+                        changed |= ui
+                            .selectable_value(&mut controls.difficult, difficult, text)
+                            .changed();
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Since: ");
+            changed |= ui
+                .add(TextEdit::singleline(&mut controls.since).hint_text("YYYY-MM-DD"))
+                .changed();
+        });
+        ui.horizontal(|ui| {
+            ui.label("Until: ");
+            changed |= ui
+                .add(TextEdit::singleline(&mut controls.until).hint_text("YYYY-MM-DD"))
+                .changed();
+        });
+
+        // Word lists which games have been played with
+        let mut word_lists: Vec<&String> = games
+            .iter()
+            .filter_map(|game| game.word_list.as_ref())
+            .collect();
+        word_lists.sort();
+        word_lists.dedup();
+
+        ui.horizontal(|ui| {
+            ui.label("List:  ");
+            ComboBox::from_id_source("stats_word_list")
+                .selected_text(controls.word_list.as_deref().unwrap_or("All"))
+                .show_ui(ui, |ui| {
+                    changed |= ui
+                        .selectable_value(&mut controls.word_list, None, "All")
+                        .changed();
+                    for word_list in word_lists {
+                        changed |= ui
+                            .selectable_value(
+                                &mut controls.word_list,
+                                Some(word_list.to_string()),
+                                word_list,
+                            )
+                            .changed();
+                    }
+                });
+        });
+    });
+    changed
+}
+
+/// Overview of the statistics
+fn overview(ui: &mut egui::Ui, dark: bool, stats: &Stats, top: usize) {
+    ui.label(
+        RichText::new(format!("Wins:  {}", stats.get_wins()))
+            .strong()
+            .color(if dark {
+                colors::GREEN
+            } else {
+                colors::DARK_MODE_GREEN
+            }),
+    );
+    ui.label(
+        RichText::new(format!("Fails: {}", stats.get_fails()))
+            .strong()
+            .color(if dark {
+                colors::YELLOW
+            } else {
+                colors::DARK_MODE_YELLOW
+            }),
+    );
+    ui.label(RichText::new(format!("Average tries: {:.2}", stats.get_average_tries())).strong());
+
+    ui.label("");
+
+    ui.label(RichText::new("Favorite words:").strong());
+    for (word, times) in stats.get_favorite_words(top) {
+        ui.label(format!("{word}: used {times} times"));
+    }
+}
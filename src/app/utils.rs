@@ -14,15 +14,15 @@ pub fn get_screen_height(ui: &egui::Ui) -> f32 {
 
 /// Check if the device is a phone
 pub fn is_phone(ui: &egui::Ui) -> bool {
-    return keyboard::get_keyboard_size_factor(ui) < 1.0;
+    keyboard::get_keyboard_size_factor(ui) < 1.0
 }
 
 // Make a smooth transition when color changes
 pub fn animate_color(ctx: &egui::Context, id: String, color: Color32) -> Color32 {
     const DURATION: f32 = 0.5;
 
-    fn animate_value(ctx: &egui::Context, id: &String, suffix: &str, value: u8) -> u8 {
-        ctx.animate_value_with_time(Id::new(id.clone() + suffix), value as f32, DURATION) as u8
+    fn animate_value(ctx: &egui::Context, id: &str, suffix: &str, value: u8) -> u8 {
+        ctx.animate_value_with_time(Id::new(id.to_string() + suffix), value as f32, DURATION) as u8
     }
 
     let r = animate_value(ctx, &id, "r", color.r());
@@ -1,31 +1,25 @@
 use std::path::PathBuf;
 
-#[cfg(not(target_arch = "wasm32"))]
-use std::{path::Path};
-
-use std::{fs::File, io::Read};
-
-use clap::Parser;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand, ValueEnum};
 use serde::{Deserialize, Serialize};
 
-use super::read_word_list;
+use super::builtin_languages;
+use super::game::{GuessStatus, LetterStatus, WORD_LENGTH};
+use super::language::{self, Language, DEFAULT_LANGUAGE};
+use super::packs;
+use super::stats::{self, Filter};
+#[cfg(not(target_arch = "wasm32"))]
+use super::words::{self, Diagnostic};
 
 pub const DEFAULT_DAY: u32 = 1;
 pub const DEFAULT_SEED: u64 = 19260817;
 
-/// Returns the successor of the identity element of addition
-fn get_one() -> Option<u32> {
-    Some(DEFAULT_DAY)
-}
+/// Name of the builtin answer list, as recorded along with games
+pub const BUILTIN_WORD_LIST: &str = "builtin";
 
-/// The birthday of you-know-who
-fn get_default_seed() -> Option<u64> {
-    Some(DEFAULT_SEED)
-}
-
-/// Command line arguments
-#[derive(Parser, Debug, Serialize, Deserialize, Default)]
-#[clap(author = "abmfy", about = "A Wordle game, refined")]
+/// Options of games, given to `play` or without a subcommand, and in config files
+#[derive(Parser, Debug, Serialize, Deserialize, Default, PartialEq)]
 pub struct Args {
     /// Specify the answer
     #[clap(short, long)]
@@ -46,18 +40,38 @@ pub struct Args {
     #[serde(default)]
     pub stats: bool,
 
+    /// Show extended statistics, along with --stats
+    #[clap(short = 'x', long)]
+    #[serde(default)]
+    pub extended: bool,
+
+    #[clap(flatten)]
+    #[serde(flatten)]
+    pub filter: StatsFilter,
+
     /// Specify current day
     #[clap(short, long, conflicts_with = "word",
         value_parser=clap::value_parser!(u32).range(1..))
     ]
-    #[serde(default = "get_one")]
     pub day: Option<u32>,
 
     /// Specify random seed
     #[clap(short, long, conflicts_with = "word")]
-    #[serde(default = "get_default_seed")]
     pub seed: Option<u64>,
 
+    /// Specify how to choose random answers [default: uniform]
+    #[clap(long, value_name = "STRATEGY", value_enum, conflicts_with = "word")]
+    pub strategy: Option<Strategy>,
+
+    /// Specify the average guesses of answers chosen by the difficulty strategy
+    #[clap(long, value_name = "GUESSES")]
+    pub target_difficulty: Option<f64>,
+
+    /// Avoid random answers already in the history of the player
+    #[clap(long, conflicts_with = "word")]
+    #[serde(default)]
+    pub fresh: bool,
+
     /// Specify the final answer list
     #[clap(short, long, value_parser = is_valid_word_list, value_name = "FILE")]
     pub final_set: Option<PathBuf>,
@@ -66,58 +80,366 @@ pub struct Args {
     #[clap(short, long, value_parser = is_valid_word_list, value_name = "FILE")]
     pub acceptable_set: Option<PathBuf>,
 
+    /// Play a puzzle pack, instead of the word lists
+    #[clap(short = 'P', long, value_name = "NAME", conflicts_with_all = &["final-set", "acceptable-set"])]
+    pub pack: Option<String>,
+
+    /// Specify a directory to find puzzle packs in, besides builtin ones
+    #[clap(long, value_name = "DIR", requires = "pack")]
+    pub pack_dir: Option<PathBuf>,
+
+    /// Play in a language, which decides the alphabet, the keyboard and the builtin word lists
+    #[clap(short = 'L', long, value_name = "NAME", value_parser = is_valid_language, global = true)]
+    pub language: Option<String>,
+
+    /// Take accented letters as their base letters in feedback, such as 'Á' as 'A'
+    #[clap(long)]
+    #[serde(default)]
+    pub ignore_accents: bool,
+
+    /// Load a supplementary dictionary, whose entries take precedence over builtin ones
+    #[clap(long, value_name = "FILE", multiple_occurrences = true)]
+    #[serde(default)]
+    pub dictionary: Vec<PathBuf>,
+
     /// Enable state saving and specify save file
     #[clap(short = 'S', long, value_name = "FILE")]
     pub state: Option<PathBuf>,
 
-    /// Specify default parameters from a JSON file
+    /// Specify the player profile, instead of asking for a name
+    #[clap(short, long, value_name = "NAME")]
+    pub player: Option<String>,
+
+    /// Compare statistics of all players in the state file
+    #[clap(short = 'C', long)]
+    #[serde(default)]
+    pub compare: bool,
+
+    /// Specify a config file, read as TOML or YAML by its extension, or as JSON otherwise
     #[clap(short, long, value_name = "FILE")]
     pub config: Option<PathBuf>,
 
-    /// GUI mode
-    #[clap(short, long)]
+    /// Ignore config files of the user and of the current directory, and environment variables,
+    /// so that scripts and bots play the same anywhere. A file given by --config is still read
+    #[clap(long)]
+    #[serde(default)]
+    pub no_config: bool,
+
+    /// Specify the format of input and output for bots [default: plain]
+    #[clap(long, value_name = "PROTOCOL", value_enum, conflicts_with = "tui")]
+    pub protocol: Option<Protocol>,
+
+    /// Full-screen terminal UI mode, which redraws in place and takes input key by key
+    #[clap(short = 'T', long)]
     #[serde(default)]
+    pub tui: bool,
+
+    /// Join a race hosted at an address, such as 127.0.0.1:7878, instead of playing alone
+    #[clap(long, value_name = "ADDR", conflicts_with_all = &["word", "random", "tui", "protocol"])]
+    pub join: Option<String>,
+
+    /// Watch the race joined instead, seeing every guess along with its letters
+    #[clap(long, requires = "join")]
+    #[serde(default)]
+    pub spectate: bool,
+
+    /// Play back a race recorded by race --record, verifying it with the word lists given
+    #[clap(
+        long,
+        value_name = "FILE",
+        conflicts_with_all = &["word", "random", "tui", "protocol", "join"]
+    )]
+    pub replay: Option<PathBuf>,
+}
+
+/// Options on which games are counted in statistics, and how many entries to show
+#[derive(clap::Args, Debug, Serialize, Deserialize, Default, PartialEq)]
+pub struct StatsFilter {
+    /// Specify how many entries to show in statistics lists [default: 5]
+    #[clap(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub stats_top: Option<u64>,
+
+    /// Only count games played on or after a date in statistics
+    #[clap(long, value_name = "YYYY-MM-DD")]
+    pub stats_since: Option<NaiveDate>,
+
+    /// Only count games played on or before a date in statistics
+    #[clap(long, value_name = "YYYY-MM-DD")]
+    pub stats_until: Option<NaiveDate>,
+
+    /// Only count games played in a mode in statistics
+    #[clap(long, value_name = "MODE", value_enum)]
+    pub stats_mode: Option<Mode>,
+
+    /// Only count games played with an answer list in statistics
+    #[clap(long, value_name = "NAME")]
+    pub stats_word_list: Option<String>,
+}
+
+/// Options of the GUI, which takes the rest in its settings
+#[derive(clap::Args, Debug, Default)]
+pub struct GuiArgs {
+    /// Load a supplementary dictionary, whose entries take precedence over builtin ones
+    #[clap(long, value_name = "FILE", multiple_occurrences = true)]
+    pub dictionary: Vec<PathBuf>,
+
+    /// Join a race hosted at an address, such as 127.0.0.1:7878, instead of playing alone
+    #[clap(long, value_name = "ADDR")]
+    pub join: Option<String>,
+
+    /// Specify the player racing
+    #[clap(short, long, value_name = "NAME")]
+    pub player: Option<String>,
+
+    /// Play back a race recorded by race --record
+    #[clap(long, value_name = "FILE", conflicts_with = "join")]
+    pub replay: Option<PathBuf>,
+}
+
+/// Game modes
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Mode {
+    Normal,
+    #[serde(alias = "hard")]
+    Difficult,
+}
+
+/// Strategies to choose random answers
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Strategy {
+    /// Every answer is equally likely
+    Uniform,
+    /// Frequent words are more likely, as told by the metadata of word lists
+    Frequency,
+    /// Answers closer to a target difficulty come first
+    Difficulty,
+}
+
+/// Formats of input and output, for programs driving the game
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// Words in, and status lines such as `RRYGX XXXX...` or `CORRECT 3` out
+    Plain,
+    /// A JSON object per line, both requests and responses
+    Jsonl,
+}
+
+/// Command line, which is either a subcommand or a game with arguments, as `play` takes.
+/// Subcommands are kept out of `Args` so that config files can be overridden
+/// by `Cli::update_from` without touching them
+#[derive(Parser, Debug)]
+#[clap(author = "abmfy", about = "A Wordle game, refined")]
+pub struct Cli {
+    #[clap(flatten)]
+    pub args: Args,
+
+    /// GUI mode, the same as the gui subcommand
+    #[clap(short, long, conflicts_with_all = &["protocol", "tui"])]
     pub gui: bool,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Commands, where games are played by `play` as well as without a subcommand
+#[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Command {
+    /// Play games in the terminal, which is the default
+    Play(Args),
+
+    /// Play in the GUI
+    Gui(GuiArgs),
+
+    /// Show statistics of a player without playing
+    Stats {
+        /// The state file to read games from
+        #[clap(short = 'S', long, value_name = "FILE")]
+        state: PathBuf,
+
+        /// Specify the player profile, the default one if not given
+        #[clap(short, long, value_name = "NAME")]
+        player: Option<String>,
+
+        /// Show extended statistics as well
+        #[clap(short = 'x', long)]
+        extended: bool,
+
+        /// Compare statistics of all players in the state file instead
+        #[clap(short = 'C', long)]
+        compare: bool,
+
+        #[clap(flatten)]
+        filter: StatsFilter,
+    },
+
+    /// Suggest the next guess, with the feedback of former guesses such as CRANE:RRYGR
+    Solve {
+        #[clap(value_name = "GUESS:FEEDBACK", value_parser = parse_feedback)]
+        feedback: Vec<(String, GuessStatus)>,
+
+        /// Specify the final answer list, from which the answer is guessed
+        #[clap(short, long, value_parser = is_valid_word_list, value_name = "FILE")]
+        final_set: Option<PathBuf>,
+    },
+
+    /// Import games from a JSON state file into another state file, such as an SQLite database
+    ImportState {
+        /// The JSON state file to import from
+        #[clap(value_name = "FROM")]
+        from: PathBuf,
+
+        /// The state file to import into, whose format is decided by its extension
+        #[clap(value_name = "TO")]
+        to: PathBuf,
+    },
+
+    /// Check word lists and report problems line by line, checking builtin lists if none is given
+    LintWords {
+        /// The final answer list to check
+        #[clap(short, long, value_parser = is_valid_word_list, value_name = "FILE")]
+        final_set: Option<PathBuf>,
+
+        /// The acceptable word list to check
+        #[clap(short, long, value_parser = is_valid_word_list, value_name = "FILE")]
+        acceptable_set: Option<PathBuf>,
+    },
+
+    /// List puzzle packs, builtin ones and those in a directory
+    Packs {
+        /// The directory to find puzzle packs in
+        #[clap(long, value_name = "DIR")]
+        dir: Option<PathBuf>,
+    },
+
+    /// Show definitions of words in the dictionary
+    Define {
+        #[clap(value_name = "WORD", required = true)]
+        words: Vec<String>,
+
+        /// Load a supplementary dictionary, whose entries take precedence over builtin ones
+        #[clap(long, value_name = "FILE", multiple_occurrences = true)]
+        dictionary: Vec<PathBuf>,
+    },
+
+    /// Host games over HTTP on localhost, with the word lists and the state file given
+    Serve {
+        /// The port to listen on, chosen by the system if 0
+        #[clap(long, value_name = "PORT", default_value_t = 8000)]
+        port: u16,
+    },
+
+    /// Host a race on localhost, where players joining by --join guess the same answer
+    Race {
+        /// The port to listen on, chosen by the system if 0
+        #[clap(long, value_name = "PORT", default_value_t = 7878)]
+        port: u16,
+
+        /// How many players the race starts with
+        #[clap(long, value_name = "N", default_value_t = 2,
+            value_parser = clap::value_parser!(u32).range(1..))
+        ]
+        players: u32,
+
+        /// Save a replay of the race, which can be played back by --replay
+        #[clap(long, value_name = "FILE")]
+        record: Option<PathBuf>,
+    },
+
+    /// Run a tournament defined in a JSON file, where each participant plays every round in turn
+    Tournament {
+        /// The definition of the tournament, with its participants, mode and rounds
+        #[clap(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Export the leaderboard, as Markdown if the extension is .md, or as JSON otherwise
+        #[clap(long, value_name = "FILE")]
+        export: Option<PathBuf>,
+    },
+
+    /// Manage word lists
+    Words {
+        #[clap(subcommand)]
+        command: WordsCommand,
+    },
+
+    /// Manage settings of games, given before the subcommand as to games
+    Config {
+        #[clap(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+/// Commands on settings, which are given by config files, environment variables and flags
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommand {
+    /// Print the effective settings of games, and where each one comes from
+    Show,
+}
+
+/// Commands on word lists, which are checked the same way as in games before used
+#[derive(Subcommand, Debug)]
+pub enum WordsCommand {
+    /// Show words only in one of two lists
+    Diff {
+        #[clap(value_parser = is_valid_word_list, value_name = "FILE")]
+        left: PathBuf,
+
+        #[clap(value_parser = is_valid_word_list, value_name = "FILE")]
+        right: PathBuf,
+    },
+
+    /// Print the words in any of the lists, sorted and without duplicates
+    Merge {
+        #[clap(value_parser = is_valid_word_list, value_name = "FILE", required = true)]
+        lists: Vec<PathBuf>,
+    },
+
+    /// Print the words of a list which are not builtin answers
+    SubtractBuiltin {
+        #[clap(value_parser = is_valid_word_list, value_name = "FILE")]
+        list: PathBuf,
+    },
+
+    /// Show how often each letter is used, in total and at each position
+    Stats {
+        #[clap(value_parser = is_valid_word_list, value_name = "FILE")]
+        list: PathBuf,
+    },
 }
 
 impl Args {
+    /// Check the word lists given, returning warnings if they can be used,
+    /// or all the errors found otherwise
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn load_defaults(path: &Path) -> Result<Args, ()> {
-        let mut contents = String::new();
-        if let Ok(mut file) = File::open(path) {
-            file.read_to_string(&mut contents).unwrap();
-        } else {
-            return Err(());
-        }
-        let args = serde_json::from_str::<Args>(contents.as_str());
-        if let Ok(args) = args {
-            Ok(args)
-        } else {
-            Err(())
+    pub fn validate_word_list(&self) -> Result<Vec<Diagnostic>, String> {
+        if let Some(ref path) = self.final_set {
+            is_valid_word_list(path.to_str().unwrap())?;
         }
-    }
-
-    #[cfg(not(target_arch = "wasm32"))]
-    pub fn validate_word_list(&self) -> Result<(), String> {
-        if self.final_set.is_some() {
-            if let Err(message) =
-                is_valid_word_list(self.final_set.as_ref().unwrap().to_str().unwrap())
-            {
-                return Err(message);
-            }
+        if let Some(ref path) = self.acceptable_set {
+            is_valid_word_list(path.to_str().unwrap())?;
         }
-        if self.acceptable_set.is_some() {
-            if let Err(message) =
-                is_valid_word_list(self.acceptable_set.as_ref().unwrap().to_str().unwrap())
-            {
-                return Err(message);
-            }
+        // Builtin lists are known to be valid
+        if self.final_set.is_none() && self.acceptable_set.is_none() {
+            return Ok(vec![]);
         }
-        Ok(())
+
+        let diagnostics = words::lint_lists(
+            self.acceptable_set.as_deref(),
+            self.final_set.as_deref(),
+            self.get_language(),
+        );
+        if words::has_errors(&diagnostics) {
+            return Err(words::format_errors(&diagnostics));
+        }
+        Ok(diagnostics)
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn validate(&self, answer_list: &Vec<String>) -> Result<(), String> {
+    pub fn validate(&self, answer_list: &[String]) -> Result<(), String> {
         if let Some(day) = self.day {
             if day == 0 {
                 return Err("Day must be greater than 0!".to_string());
@@ -129,10 +451,15 @@ impl Args {
             }
         }
         if let Some(ref word) = self.word {
-            if !answer_list.contains(&word.to_uppercase()) {
+            let language = self.get_language();
+            if language.find_word(&language.to_upper(word), answer_list).is_none() {
                 return Err("Provided answer is not in the answer words list!".to_string());
             }
         }
+        // Languages in config files are not checked when parsed
+        if let Some(ref language) = self.language {
+            is_valid_language(language)?;
+        }
         // Conflicting arguments
         if self.word.is_some() && self.random {
             return Err("Conflicting arguments: --word and --random".to_string());
@@ -144,12 +471,121 @@ impl Args {
         if self.day.is_some() && !self.random {
             return Err("--day requires --random".to_string());
         }
+        if self.strategy.is_some() && !self.random {
+            return Err("--strategy requires --random".to_string());
+        }
+        if self.fresh && !self.random {
+            return Err("--fresh requires --random".to_string());
+        }
+        if self.target_difficulty.is_some() != (self.strategy == Some(Strategy::Difficulty)) {
+            return Err(
+                "--strategy difficulty and --target-difficulty require each other".to_string(),
+            );
+        }
+        if self
+            .target_difficulty
+            .is_some_and(|target| target.is_nan() || target < 1.0)
+        {
+            return Err("Target difficulty must be at least 1!".to_string());
+        }
+        if self.extended && !self.stats {
+            return Err("--extended requires --stats".to_string());
+        }
+        if !self.stats && !self.compare && !self.get_filter().is_empty() {
+            return Err("Statistics filters require --stats or --compare".to_string());
+        }
+        if self.compare && self.state.is_none() {
+            return Err("--compare requires --state".to_string());
+        }
 
         Ok(())
     }
+
+    /// Get the name of the answer list, which is recorded along with games
+    pub fn get_word_list_name(&self) -> String {
+        if let Some(ref pack) = self.pack {
+            return packs::get_word_list_name(pack);
+        }
+        // The acceptable list is used as answer list if the latter is not provided
+        match self.final_set.as_ref().or(self.acceptable_set.as_ref()) {
+            Some(path) => path.display().to_string(),
+            // Builtin lists of other languages are told apart by their names
+            None => match self.get_language().name {
+                DEFAULT_LANGUAGE => BUILTIN_WORD_LIST.to_string(),
+                name => format!("{BUILTIN_WORD_LIST}:{name}"),
+            },
+        }
+    }
+
+    /// Get the language to play in
+    pub fn get_language(&self) -> &'static Language {
+        language::get(self.language.as_deref().unwrap_or(DEFAULT_LANGUAGE))
+    }
+
+    /// Get how many entries to show in statistics lists
+    pub fn get_stats_top(&self) -> usize {
+        self.filter.get_stats_top()
+    }
+
+    /// Get the filter on games counted in statistics
+    pub fn get_filter(&self) -> Filter {
+        self.filter.get_filter()
+    }
+
+    /// Get the options of the GUI, which are all it takes from the command line
+    pub fn get_gui_args(&self) -> GuiArgs {
+        GuiArgs {
+            dictionary: self.dictionary.clone(),
+            join: self.join.clone(),
+            player: self.player.clone(),
+            replay: self.replay.clone(),
+        }
+    }
+}
+
+impl StatsFilter {
+    /// Get how many entries to show in statistics lists
+    pub fn get_stats_top(&self) -> usize {
+        self.stats_top
+            .map_or(stats::DEFAULT_TOP, |top| top as usize)
+    }
+
+    /// Get the filter on games counted in statistics
+    pub fn get_filter(&self) -> Filter {
+        Filter {
+            since: self.stats_since,
+            until: self.stats_until,
+            difficult: self.stats_mode.map(|mode| mode == Mode::Difficult),
+            word_list: self.stats_word_list.clone(),
+        }
+    }
+}
+
+/// Parse the feedback of a guess, such as `CRANE:RRYGR`, where R, Y and G are for red,
+/// yellow and green letters
+fn parse_feedback(feedback: &str) -> Result<(String, GuessStatus), String> {
+    let (guess, status) = feedback
+        .split_once(':')
+        .ok_or_else(|| "Expected GUESS:FEEDBACK".to_string())?;
+    let status: Vec<LetterStatus> = status
+        .chars()
+        .map(|c| match c.to_ascii_uppercase() {
+            'R' => Ok(LetterStatus::Red),
+            'Y' => Ok(LetterStatus::Yellow),
+            'G' => Ok(LetterStatus::Green),
+            _ => Err(format!("Unknown letter status '{c}', expected R, Y or G")),
+        })
+        .collect::<Result<_, _>>()?;
+    let status: GuessStatus = status
+        .try_into()
+        .map_err(|_| format!("Expected {WORD_LENGTH} letters of feedback"))?;
+    if language::count_letters(guess) != WORD_LENGTH {
+        return Err(format!("Expected a guess of {WORD_LENGTH} letters"));
+    }
+    Ok((guess.to_string(), status))
 }
 
-/// Check if a word list file is in valid format, and store the words
+/// Check if a word list file exists. Its contents are checked by `words::lint_lists`
 fn is_valid_word_list(path: &str) -> Result<PathBuf, String> {
     let path = PathBuf::from(path);
     // Check if the file exists
@@ -159,23 +595,19 @@ fn is_valid_word_list(path: &str) -> Result<PathBuf, String> {
         return Err("Not a file".to_string());
     }
 
-    let mut file = File::open(path.as_path()).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-
-    // Check if the file is in valid format
-    let list = read_word_list(&path);
-    // Check if the words are in length 5, and consists of only latin letters
-    if !list
-        .iter()
-        .all(|word| word.len() == 5 && word.chars().all(|c| c.is_ascii_alphabetic()))
-    {
-        return Err("Invalid word list: words should consist of 5 latin letters".to_string());
-    };
-
-    if list.len() == 0 {
-        return Err("Invalid word list: empty file".to_string());
-    }
-
     Ok(path)
 }
+
+/// Check if a language is builtin
+fn is_valid_language(name: &str) -> Result<String, String> {
+    match language::find(name) {
+        Some(language) => Ok(language.name.to_string()),
+        None => {
+            let names: Vec<&str> = builtin_languages::LANGUAGES
+                .iter()
+                .map(|language| language.name)
+                .collect();
+            Err(format!("Unknown language, expected one of: {}", names.join(", ")))
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::builtin_words;
+use crate::language::Language;
+
+/// Languages shipped with the game, the default one first and the others sorted by name
+pub const LANGUAGES: &[Language] = &[
+    Language {
+        name: "en",
+        title: "English",
+        alphabet: &[
+            "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+            "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+        ],
+        keyboard: &["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"],
+        uppercase: &[],
+        acceptable: builtin_words::ACCEPTABLE,
+        final_words: builtin_words::FINAL,
+    },
+    Language {
+        name: "de",
+        title: "Deutsch",
+        alphabet: &[
+            "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+            "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+            "Ä", "Ö", "Ü", "ß",
+        ],
+        keyboard: &["QWERTZUIOPÜ", "ASDFGHJKLÖÄ", "YXCVBNMß"],
+        // There is hardly any word starting with 'ß', so it has no upper case in word games
+        uppercase: &[("ß", "ß"), ("ẞ", "ß")],
+        acceptable: &[
+            "apfel", "bauer", "blume", "brief", "blatt", "braun", "feuer", "fisch", "gabel",
+            "glück", "hafen", "halle", "honig", "hunde", "insel", "katze", "kerze", "kleid",
+            "könig", "küche", "lampe", "leben", "licht", "nacht", "nebel", "pferd", "regen",
+            "riese", "schaf", "sonne", "spiel", "stadt", "stern", "stuhl", "tisch", "vogel",
+            "wagen", "wolke", "bäume", "mäuse", "äpfel", "türme", "übung", "größe", "klöße",
+            "abend", "angst", "berge", "boden", "dauer", "ernte", "farbe", "geist", "jagen",
+            "kraft", "löwen", "monat", "nadel", "osten", "pause", "quark", "ruder", "salat",
+            "tante", "welle", "zebra", "große", "stöße",
+        ],
+        final_words: &[
+            "apfel", "bauer", "blume", "brief", "blatt", "braun", "feuer", "fisch", "gabel",
+            "glück", "hafen", "halle", "honig", "hunde", "insel", "katze", "kerze", "kleid",
+            "könig", "küche", "lampe", "leben", "licht", "nacht", "nebel", "pferd", "regen",
+            "riese", "schaf", "sonne", "spiel", "stadt", "stern", "stuhl", "tisch", "vogel",
+            "wagen", "wolke", "bäume", "mäuse", "äpfel", "türme", "übung", "größe", "klöße",
+        ],
+    },
+    Language {
+        name: "el",
+        title: "Ελληνικά",
+        alphabet: &[
+            "Α", "Β", "Γ", "Δ", "Ε", "Ζ", "Η", "Θ", "Ι", "Κ", "Λ", "Μ", "Ν",
+            "Ξ", "Ο", "Π", "Ρ", "Σ", "Τ", "Υ", "Φ", "Χ", "Ψ", "Ω",
+        ],
+        keyboard: &["ΕΡΤΥΘΙΟΠ", "ΑΣΔΦΓΗΞΚΛ", "ΖΧΨΩΒΝΜ"],
+        // Accents are dropped in upper case
+        uppercase: &[
+            ("ά", "Α"), ("έ", "Ε"), ("ή", "Η"), ("ί", "Ι"), ("ό", "Ο"), ("ύ", "Υ"), ("ώ", "Ω"),
+            ("Ά", "Α"), ("Έ", "Ε"), ("Ή", "Η"), ("Ί", "Ι"), ("Ό", "Ο"), ("Ύ", "Υ"), ("Ώ", "Ω"),
+        ],
+        acceptable: &[
+            "σπίτι", "ήλιος", "φίλος", "λόγος", "κρασί", "παιδί", "σχολή", "νύχτα", "λίμνη",
+            "πέτρα", "φωτιά", "αέρας", "άλογο", "ψάρια", "πουλί", "χαρτί", "πόρτα", "κήπος",
+            "φύλλο", "χιόνι", "βροχή", "θείος", "ξένος", "αλάτι", "βουνό", "άμμος", "ομάδα",
+            "μπάλα", "κουτί", "στόμα", "χέρια", "πόδια", "μάτια",
+        ],
+        final_words: &[
+            "σπίτι", "ήλιος", "φίλος", "λόγος", "κρασί", "παιδί", "σχολή", "νύχτα", "λίμνη",
+            "πέτρα", "φωτιά", "αέρας", "άλογο", "ψάρια", "πουλί", "χαρτί", "πόρτα", "κήπος",
+            "φύλλο", "χιόνι", "βροχή", "θείος", "ξένος", "αλάτι", "βουνό", "άμμος",
+        ],
+    },
+    Language {
+        name: "es",
+        title: "Español",
+        alphabet: &[
+            "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+            "N", "Ñ", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y",
+            "Z",
+        ],
+        keyboard: &["QWERTYUIOP", "ASDFGHJKLÑ", "ZXCVBNM"],
+        // Accented vowels are not letters of their own, unlike 'Ñ'
+        uppercase: &[],
+        acceptable: &[
+            "perro", "gatos", "casas", "mundo", "playa", "fuego", "tigre", "libro", "cielo",
+            "nieve", "plato", "barco", "campo", "carta", "dulce", "fruta", "huevo", "juego",
+            "leche", "llave", "mango", "monte", "noche", "papel", "pared", "piano", "punto",
+            "queso", "reloj", "salsa", "siglo", "suelo", "tarde", "texto", "torre", "verde",
+            "viaje", "zorro", "sueño", "niños", "señal", "abrir", "actor", "amigo", "baile",
+            "besos", "bolsa", "brazo", "calle", "canto", "cerdo", "clase", "coche", "corte",
+            "dedos", "falda", "gorra", "hielo", "largo", "marea", "moral", "nubes", "otoño",
+            "pelea", "pluma", "radio", "ruido", "sabor", "techo", "valor", "árbol", "lápiz",
+            "fácil", "ángel", "limón", "ratón", "avión", "débil", "móvil", "álbum", "túnel",
+            "jamón", "melón", "balón", "salón", "razón",
+        ],
+        final_words: &[
+            "perro", "gatos", "casas", "mundo", "playa", "fuego", "tigre", "libro", "cielo",
+            "nieve", "plato", "barco", "campo", "carta", "dulce", "fruta", "huevo", "juego",
+            "leche", "llave", "mango", "monte", "noche", "papel", "pared", "piano", "punto",
+            "queso", "reloj", "salsa", "siglo", "suelo", "tarde", "texto", "torre", "verde",
+            "viaje", "zorro", "sueño", "niños", "señal", "árbol", "lápiz", "fácil", "ángel",
+            "limón", "ratón", "avión",
+        ],
+    },
+    Language {
+        name: "pt",
+        title: "Português",
+        alphabet: &[
+            "A", "B", "C", "D", "E", "F", "G", "H", "I", "J", "K", "L", "M",
+            "N", "O", "P", "Q", "R", "S", "T", "U", "V", "W", "X", "Y", "Z",
+        ],
+        // Accented letters and 'Ç' are typed as their base letters
+        keyboard: &["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"],
+        uppercase: &[],
+        acceptable: &[
+            "amigo", "livro", "festa", "praia", "mundo", "tempo", "corpo", "noite", "terra",
+            "carro", "porta", "fundo", "papel", "vento", "verde", "campo", "nuvem", "chuva",
+            "peixe", "gente", "sorte", "canto", "feliz", "órgão", "pátio", "série", "fácil",
+            "lápis", "maçãs", "irmão", "limão", "avião", "fogão", "nação", "sábio", "único",
+            "árabe", "ótimo", "tênis", "época", "pólen", "caçar", "peças", "vocês", "balão",
+            "ações", "mágoa", "índio", "hífen", "tórax",
+        ],
+        final_words: &[
+            "amigo", "livro", "festa", "praia", "mundo", "tempo", "corpo", "noite", "terra",
+            "carro", "porta", "fundo", "papel", "vento", "verde", "campo", "nuvem", "chuva",
+            "peixe", "gente", "sorte", "canto", "feliz", "órgão", "pátio", "série", "fácil",
+            "lápis", "maçãs", "irmão", "limão", "avião", "fogão", "nação",
+        ],
+    },
+    Language {
+        name: "ru",
+        title: "Русский",
+        alphabet: &[
+            "А", "Б", "В", "Г", "Д", "Е", "Ё", "Ж", "З", "И", "Й", "К", "Л",
+            "М", "Н", "О", "П", "Р", "С", "Т", "У", "Ф", "Х", "Ц", "Ч", "Ш",
+            "Щ", "Ъ", "Ы", "Ь", "Э", "Ю", "Я",
+        ],
+        keyboard: &["ЙЦУКЕНГШЩЗХЪ", "ФЫВАПРОЛДЖЭ", "ЯЧСМИТЬБЮЁ"],
+        uppercase: &[],
+        acceptable: &[
+            "книга", "слово", "земля", "город", "место", "время", "голос", "дверь", "птица",
+            "школа", "сосна", "берег", "ветер", "огонь", "океан", "песня", "поезд", "улица",
+            "фильм", "чашка", "кошка", "зебра", "масло", "сахар", "мороз", "полёт", "пчёлы",
+            "объём", "съезд", "рынок", "ручка", "живот", "шапка", "щётка", "цифра", "юноша",
+            "ягода", "холод", "фрукт", "адрес", "вагон", "глаза", "дождь", "завод", "игрок",
+            "лампа", "ложка", "мешок", "нитка", "пламя", "радио", "сумка", "тесто", "шутка",
+            "мысль", "палец",
+        ],
+        final_words: &[
+            "книга", "слово", "земля", "город", "место", "время", "голос", "дверь", "птица",
+            "школа", "сосна", "берег", "ветер", "огонь", "океан", "песня", "поезд", "улица",
+            "фильм", "чашка", "кошка", "зебра", "масло", "сахар", "мороз", "полёт", "пчёлы",
+            "объём", "съезд", "рынок", "ручка", "живот", "шапка", "щётка", "цифра", "юноша",
+            "ягода", "холод", "фрукт",
+        ],
+    },
+];
@@ -0,0 +1,46 @@
+use crate::packs::BuiltinPack;
+
+/// Packs shipped with the game, sorted by name
+pub const PACKS: &[BuiltinPack] = &[
+    BuiltinPack {
+        name: "animals",
+        title: "Animals",
+        description: "Creatures of land, sea and sky",
+        language: "en",
+        answers: &[
+            "zebra", "tiger", "horse", "sheep", "moose", "camel", "otter", "skunk", "hyena",
+            "lemur", "sloth", "eagle", "raven", "snake", "shark", "whale", "trout", "bison",
+            "gecko", "koala", "panda", "llama", "hippo", "rhino", "mouse", "goose", "crane",
+            "stork", "viper", "cobra", "heron", "finch", "robin", "macaw", "tapir", "dingo",
+            "hound", "bunny", "owlet", "squid", "snail",
+        ],
+        acceptable: None,
+    },
+    BuiltinPack {
+        name: "chemistry",
+        title: "Chemistry",
+        description: "Elements, compounds and the lab bench",
+        language: "en",
+        answers: &[
+            "oxide", "ether", "boron", "xenon", "radon", "argon", "ozone", "helix", "ionic",
+            "ester", "amine", "azide", "phase", "flask", "metal", "salts", "bonds", "atoms",
+            "alkyl", "acids", "alloy", "anion", "assay", "basic", "fumes", "gases", "lipid",
+            "vapor", "nitro", "molar", "titan", "orbit", "polar", "latex", "resin",
+        ],
+        acceptable: None,
+    },
+    BuiltinPack {
+        name: "programming",
+        title: "Programming",
+        description: "Words from the life of a programmer",
+        language: "en",
+        answers: &[
+            "array", "class", "debug", "float", "query", "stack", "queue", "parse", "logic",
+            "tuple", "macro", "bytes", "cache", "fetch", "merge", "patch", "scope", "token",
+            "shell", "yield", "await", "break", "while", "index", "print", "input", "error",
+            "panic", "trait", "crate", "loops", "build", "clone", "coder", "pixel", "robot",
+            "spawn", "throw", "catch", "alias", "const", "async", "mutex",
+        ],
+        acceptable: None,
+    },
+];
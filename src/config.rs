@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::{ArgMatches, FromArgMatches, ValueSource};
+use serde_json::{Map, Value};
+
+use crate::args::{self, Args};
+
+/// Prefix of environment variables of settings, such as `WORDLE_FINAL_SET`
+pub const ENV_PREFIX: &str = "WORDLE_";
+
+/// Extensions of config files, in the order they are looked for
+const EXTENSIONS: [&str; 4] = ["toml", "yaml", "yml", "json"];
+
+/// Settings by their names, as the fields of `Args` are serialized
+type Table = Map<String, Value>;
+
+/// Where a setting comes from, from the lowest precedence to the highest
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    /// The config file under the config directory of the user
+    User(PathBuf),
+    /// The config file in the current directory
+    Project(PathBuf),
+    /// The config file given by `--config`
+    File(PathBuf),
+    Env(String),
+    Flag,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::User(path) => write!(f, "user:{}", path.display()),
+            Source::Project(path) => write!(f, "project:{}", path.display()),
+            Source::File(path) => write!(f, "config:{}", path.display()),
+            Source::Env(name) => write!(f, "env:{name}"),
+            Source::Flag => write!(f, "flag"),
+        }
+    }
+}
+
+/// Effective settings, along with where each one comes from
+pub struct Settings {
+    pub args: Args,
+    values: Table,
+    sources: BTreeMap<String, Source>,
+}
+
+impl Settings {
+    /// Print every setting with its value and source, as `NAME VALUE SOURCE` lines in non-tty mode
+    pub fn print(&self, is_tty: bool) {
+        for (name, value) in &self.values {
+            let source = &self.sources[name];
+            let value = match value {
+                Value::Null => get_default(name).unwrap_or(Value::Null),
+                value => value.clone(),
+            };
+            if !is_tty {
+                println!("{name} {value} {source}");
+            } else if *source == Source::Default {
+                println!(
+                    "{} = {value} {}",
+                    console::style(name).bold(),
+                    console::style(format!("({source})")).dim()
+                );
+            } else {
+                println!(
+                    "{} = {value} {}",
+                    console::style(name).bold().blue(),
+                    console::style(format!("({source})")).green()
+                );
+            }
+        }
+    }
+}
+
+/// The value games take for a setting left out, if it is not just null
+fn get_default(name: &str) -> Option<Value> {
+    match name {
+        "seed" => Some(args::DEFAULT_SEED.into()),
+        "day" => Some(args::DEFAULT_DAY.into()),
+        _ => None,
+    }
+}
+
+/// Find a config file named `name` in a directory, with any of the extensions supported
+fn find_file(dir: &Path, name: &str) -> Option<PathBuf> {
+    EXTENSIONS
+        .iter()
+        .map(|ext| dir.join(name).with_extension(ext))
+        .find(|path| path.is_file())
+}
+
+/// The config file of the user, such as `~/.config/wordle/config.toml`
+pub fn get_user_file() -> Option<PathBuf> {
+    find_file(&dirs_next::config_dir()?.join("wordle"), "config")
+}
+
+/// The config file of the project, such as `wordle.toml` in the current directory
+pub fn get_project_file() -> Option<PathBuf> {
+    find_file(&env::current_dir().ok()?, "wordle")
+}
+
+/// Read a config file, as TOML or YAML by its extension, or as JSON otherwise
+fn read_file(path: &Path) -> Result<Table, String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to load config file {}: {e}", path.display()))?;
+    let value = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str::<Value>(&contents).map_err(|e| e.to_string()),
+        Some("yaml" | "yml") => serde_yaml::from_str::<Value>(&contents).map_err(|e| e.to_string()),
+        _ => serde_json::from_str::<Value>(&contents).map_err(|e| e.to_string()),
+    };
+    match value {
+        Ok(Value::Object(table)) => Ok(table),
+        Ok(_) => Err(format!(
+            "Failed to load config file {}: expected a table of settings",
+            path.display()
+        )),
+        Err(message) => Err(format!(
+            "Failed to load config file {}: {message}",
+            path.display()
+        )),
+    }
+}
+
+/// Check every setting in a layer on its own, so that errors tell which one is wrong
+fn check(table: &Table, defaults: &Table, source: &Source) -> Result<(), String> {
+    for (name, value) in table {
+        if !defaults.contains_key(name) {
+            return Err(format!("Unknown setting {name} ({source})"));
+        }
+        let in_file = matches!(source, Source::User(_) | Source::Project(_) | Source::File(_));
+        if name == "no_config" && in_file {
+            return Err(format!("Setting {name} cannot be given in a config file ({source})"));
+        }
+        let setting = Map::from_iter([(name.clone(), value.clone())]);
+        if let Err(e) = serde_json::from_value::<Args>(Value::Object(setting)) {
+            return Err(format!("Invalid setting {name} ({source}): {e}"));
+        }
+    }
+    Ok(())
+}
+
+/// Settings in environment variables, whose values are read as YAML scalars,
+/// so that `WORDLE_DAY=3` is a number and `WORDLE_RANDOM=true` is a boolean
+fn read_env(defaults: &Table) -> Vec<(Table, Source)> {
+    defaults
+        .keys()
+        .filter_map(|name| {
+            let var = format!("{ENV_PREFIX}{}", name.to_uppercase());
+            let raw = env::var(&var).ok()?;
+            let value = serde_yaml::from_str::<Value>(&raw).unwrap_or(Value::String(raw));
+            Some((Map::from_iter([(name.clone(), value)]), Source::Env(var)))
+        })
+        .collect()
+}
+
+/// Settings given on the command line, leaving out those left to their defaults
+fn read_flags(matches: &ArgMatches) -> Result<Table, String> {
+    let args = Args::from_arg_matches(matches).map_err(|e| e.to_string())?;
+    let Value::Object(table) = serde_json::to_value(args).unwrap() else {
+        unreachable!("Args are serialized as a table");
+    };
+    Ok(table
+        .into_iter()
+        .filter(|(name, _)| {
+            // Arguments are named in kebab case, as their long flags
+            matches.value_source(name.replace('_', "-")) == Some(ValueSource::CommandLine)
+        })
+        .collect())
+}
+
+/// Resolve the settings of games, where each of the layers below overrides the former ones:
+/// defaults, the user config file, the project config file, the file given by `--config`,
+/// environment variables, and then flags on the command line.
+/// With `--no-config`, only the file given by `--config` and flags are read
+pub fn load(matches: &ArgMatches) -> Result<Settings, String> {
+    let Value::Object(defaults) = serde_json::to_value(Args::default()).unwrap() else {
+        unreachable!("Args are serialized as a table");
+    };
+
+    let flags = read_flags(matches)?;
+    let mut env = read_env(&defaults);
+    // Config files are turned off before they are read, so only by a flag or `WORDLE_NO_CONFIG`
+    let no_config = flags
+        .iter()
+        .chain(env.iter().flat_map(|(table, _)| table))
+        .any(|(name, value)| name == "no_config" && *value == Value::Bool(true));
+    let mut layers = vec![];
+    if no_config {
+        env.retain(|(table, _)| table.contains_key("no_config"));
+    } else {
+        if let Some(path) = get_user_file() {
+            layers.push((read_file(&path)?, Source::User(path)));
+        }
+        if let Some(path) = get_project_file() {
+            layers.push((read_file(&path)?, Source::Project(path)));
+        }
+    }
+    // The config file may be given by an environment variable as well
+    let file = flags
+        .get("config")
+        .or_else(|| env.iter().find_map(|(table, _)| table.get("config")))
+        .and_then(Value::as_str);
+    if let Some(path) = file {
+        let path = PathBuf::from(path);
+        layers.push((read_file(&path)?, Source::File(path)));
+    }
+    layers.extend(env);
+    layers.push((flags, Source::Flag));
+
+    let mut values = defaults.clone();
+    let mut sources: BTreeMap<String, Source> = defaults
+        .keys()
+        .map(|name| (name.clone(), Source::Default))
+        .collect();
+    for (table, source) in layers {
+        check(&table, &defaults, &source)?;
+        for (name, value) in table {
+            sources.insert(name.clone(), source.clone());
+            values.insert(name, value);
+        }
+    }
+
+    let args = serde_json::from_value(Value::Object(values.clone())).map_err(|e| e.to_string())?;
+    Ok(Settings {
+        args,
+        values,
+        sources,
+    })
+}
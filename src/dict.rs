@@ -1,10 +1,219 @@
-use lazy_static::lazy_static;
-use serde_json;
 use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, path::PathBuf};
 
-lazy_static! {
-    pub static ref DICT: HashMap<String, Vec<String>> = {
-        let json = include_str!("../assets/dict.json");
-        serde_json::from_str(json).unwrap()
+#[cfg(not(target_arch = "wasm32"))]
+mod source;
+
+/// An entry of the dictionary
+pub struct Entry {
+    pub pronunciation: Option<&'static str>,
+    pub senses: &'static [Sense],
+    /// Where the word comes from
+    pub etymology: Option<&'static str>,
+}
+
+/// A sense of a word
+pub struct Sense {
+    pub part_of_speech: Option<&'static str>,
+    pub definition: &'static str,
+    pub examples: &'static [&'static str],
+    pub synonyms: &'static [&'static str],
+}
+
+// `DICT` is generated by the build script from `assets/dict.json`
+include!(concat!(env!("OUT_DIR"), "/dict.rs"));
+
+/// Get the entry of a word in upper case from the builtin dictionary
+pub fn get(word: &str) -> Option<&'static Entry> {
+    DICT.get(word)
+}
+
+/// Leak a string, so that supplementary dictionaries share the types of the builtin one.
+/// They are loaded once and used until exit anyway
+#[cfg(not(target_arch = "wasm32"))]
+fn leak(s: String) -> &'static str {
+    Box::leak(s.into_boxed_str())
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<source::Entry> for Entry {
+    fn from(entry: source::Entry) -> Self {
+        let leak_all = |words: Vec<String>| -> &'static [&'static str] {
+            Box::leak(words.into_iter().map(leak).collect())
+        };
+        let senses: Vec<Sense> = entry
+            .senses
+            .into_iter()
+            .map(|sense| Sense {
+                part_of_speech: sense.part_of_speech.map(leak),
+                definition: leak(sense.definition),
+                examples: leak_all(sense.examples),
+                synonyms: leak_all(sense.synonyms),
+            })
+            .collect();
+        Self {
+            pronunciation: entry.pronunciation.map(leak),
+            senses: Box::leak(senses.into_boxed_slice()),
+            etymology: entry.etymology.map(leak),
+        }
+    }
+}
+
+/// The builtin dictionary along with supplementary ones, which are looked up first
+#[derive(Default)]
+pub struct Dictionary {
+    supplements: HashMap<String, Entry>,
+}
+
+impl Dictionary {
+    /// Load supplementary dictionaries, in the same format as the builtin one.
+    /// Entries of later dictionaries take precedence
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load(paths: &[PathBuf]) -> Result<Self, String> {
+        let mut supplements = HashMap::new();
+        for path in paths {
+            let source = path.display();
+            let contents =
+                fs::read_to_string(path).map_err(|e| format!("{source}: failed to read: {e}"))?;
+            let entries =
+                source::parse(&contents).map_err(|e| format!("{source}: invalid dictionary: {e}"))?;
+            // Words are looked up in upper case
+            supplements.extend(
+                entries
+                    .into_iter()
+                    .map(|(word, entry)| (word.to_uppercase(), entry.into())),
+            );
+        }
+        Ok(Self { supplements })
+    }
+
+    /// Get the entry of a word in upper case
+    pub fn get(&self, word: &str) -> Option<&Entry> {
+        self.supplements.get(word).or_else(|| get(word))
+    }
+}
+
+/// Print the definition of a word from the word list along with its entry in the dictionary,
+/// in tty mode or as plain lines. Return false if there is nothing to print
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print_definition(
+    word: &str,
+    definition: Option<&str>,
+    entry: Option<&Entry>,
+    is_tty: bool,
+) -> bool {
+    let senses = entry.map_or(&[][..], |entry| entry.senses);
+    if definition.is_none() && senses.is_empty() {
+        return false;
+    }
+
+    let styled = |text: String, style: console::Style| {
+        if is_tty {
+            style.apply_to(text).to_string()
+        } else {
+            text
+        }
     };
+    let heading = console::Style::new().bold();
+
+    match entry.and_then(|entry| entry.pronunciation) {
+        Some(pronunciation) => println!(
+            "{} {}",
+            styled(word.to_string(), heading.clone().blue()),
+            styled(format!("/{pronunciation}/:"), console::Style::new().blue())
+        ),
+        None => println!("{}", styled(format!("{word}:"), heading.clone().blue())),
+    }
+
+    // The definition from the word list comes before those from the dictionary
+    let green = console::Style::new().green();
+    let mut number = 0;
+    if let Some(definition) = definition {
+        number += 1;
+        println!("{}", styled(format!("    {number}: {definition}"), green.clone()));
+    }
+    for sense in senses {
+        number += 1;
+        match sense.part_of_speech {
+            Some(part_of_speech) => println!(
+                "{} {}",
+                styled(
+                    format!("    {number}: ({part_of_speech})"),
+                    green.clone().italic()
+                ),
+                styled(sense.definition.to_string(), green.clone())
+            ),
+            None => println!(
+                "{}",
+                styled(format!("    {number}: {}", sense.definition), green.clone())
+            ),
+        }
+        for example in sense.examples {
+            println!(
+                "       {}",
+                styled(format!("\"{example}\""), console::Style::new().italic())
+            );
+        }
+        if !sense.synonyms.is_empty() {
+            println!(
+                "       {} {}",
+                styled("Synonyms:".to_string(), heading.clone()),
+                sense.synonyms.join(", ")
+            );
+        }
+    }
+    if let Some(etymology) = entry.and_then(|entry| entry.etymology) {
+        println!(
+            "    {} {etymology}",
+            styled("Origin:".to_string(), heading.clone())
+        );
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn definitions(entry: &Entry) -> Vec<&str> {
+        entry.senses.iter().map(|sense| sense.definition).collect()
+    }
+
+    #[test]
+    fn every_entry_built() {
+        let dict = source::parse(include_str!("../assets/dict.json")).unwrap();
+        for (word, entry) in dict {
+            let built = get(&word).unwrap_or_else(|| panic!("{word} is not built"));
+            let expected: Vec<String> = entry
+                .senses
+                .into_iter()
+                .map(|sense| sense.definition)
+                .collect();
+            assert_eq!(definitions(built), expected, "{word}");
+        }
+    }
+
+    #[test]
+    fn derived_words_resolved() {
+        // Derived words share the entries of their originals
+        for (derived, original) in [("AROSE", "ARISE"), ("BEGAN", "BEGIN"), ("BEGUN", "BEGIN")] {
+            assert_eq!(
+                definitions(get(derived).unwrap()),
+                definitions(get(original).unwrap())
+            );
+        }
+        // Even if the originals are not of five letters, or spelt with accents
+        assert!(get("CACTI").unwrap().senses[0]
+            .definition
+            .starts_with("a succulent plant"));
+        assert_eq!(
+            definitions(get("ECLAT").unwrap()),
+            [
+                "brilliant display or effect",
+                "social distinction or conspicuous success"
+            ]
+        );
+        assert!(get("ÉCLAT").is_none());
+    }
 }
@@ -0,0 +1,86 @@
+// The source format of dictionaries, shared by the build script and supplementary dictionaries.
+// See `build.rs` for the format
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawEntry {
+    /// The flat format, where senses are only definitions
+    Senses(Vec<RawSense>),
+    Entry(RawFullEntry),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawFullEntry {
+    pronunciation: Option<String>,
+    senses: Vec<RawSense>,
+    etymology: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawSense {
+    Definition(String),
+    Sense(Sense),
+}
+
+/// An entry of a dictionary source
+pub struct Entry {
+    pub pronunciation: Option<String>,
+    pub senses: Vec<Sense>,
+    pub etymology: Option<String>,
+}
+
+/// A sense of a word in a dictionary source
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Sense {
+    pub part_of_speech: Option<String>,
+    pub definition: String,
+    #[serde(default)]
+    pub examples: Vec<String>,
+    #[serde(default)]
+    pub synonyms: Vec<String>,
+}
+
+impl From<RawSense> for Sense {
+    fn from(sense: RawSense) -> Self {
+        match sense {
+            RawSense::Definition(definition) => Sense {
+                part_of_speech: None,
+                definition,
+                examples: vec![],
+                synonyms: vec![],
+            },
+            RawSense::Sense(sense) => sense,
+        }
+    }
+}
+
+impl From<RawEntry> for Entry {
+    fn from(entry: RawEntry) -> Self {
+        let (pronunciation, senses, etymology) = match entry {
+            RawEntry::Senses(senses) => (None, senses, None),
+            RawEntry::Entry(entry) => (entry.pronunciation, entry.senses, entry.etymology),
+        };
+        Entry {
+            pronunciation,
+            senses: senses.into_iter().map(Sense::from).collect(),
+            etymology,
+        }
+    }
+}
+
+/// Parse a dictionary source, in either the flat or the full format
+pub fn parse(contents: &str) -> Result<BTreeMap<String, Entry>, String> {
+    let dict: BTreeMap<String, RawEntry> =
+        serde_json::from_str(contents).map_err(|e| e.to_string())?;
+    Ok(dict
+        .into_iter()
+        .map(|(word, entry)| (word, entry.into()))
+        .collect())
+}
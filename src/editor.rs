@@ -0,0 +1,218 @@
+use std::borrow::Cow;
+
+use rustyline::completion::Completer;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Helper};
+
+use crate::game::{Game, WORD_LENGTH};
+use crate::language;
+use crate::repl::{self, PREFIX};
+
+/// What the editor knows of the game, to complete and check guesses as they are typed
+struct GameHelper {
+    /// Acceptable words, sorted
+    word_list: Vec<String>,
+    /// The game going, as of the line being read
+    game: Option<Game>,
+}
+
+impl GameHelper {
+    /// Words in the list starting with a prefix, typed in any case.
+    /// At least a letter is needed, or every word would be offered
+    fn complete_word<'a>(&'a self, prefix: &str) -> impl Iterator<Item = &'a String> {
+        let prefix = match self.game {
+            Some(ref game) => game.get_language().to_upper(prefix),
+            None => prefix.to_uppercase(),
+        };
+        self.word_list
+            .iter()
+            .filter(move |word| !prefix.is_empty() && word.starts_with(&prefix))
+    }
+}
+
+impl Completer for GameHelper {
+    type Candidate = String;
+
+    /// Complete names of commands, words to look up, and guesses which fit the feedback so far
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let line = &line[..pos];
+        let Some(command) = line.strip_prefix(PREFIX) else {
+            let candidates = self
+                .complete_word(line)
+                .filter(|word| {
+                    self.game.as_ref().is_none_or(|game| {
+                        game.validate_guess(true, true, word, &self.word_list).is_ok()
+                    })
+                })
+                .cloned()
+                .collect();
+            return Ok((0, candidates));
+        };
+        match command.split_once(' ') {
+            None => Ok((
+                0,
+                repl::get_names()
+                    .filter(|name| name.starts_with(command))
+                    .map(|name| format!("{PREFIX}{name} "))
+                    .collect(),
+            )),
+            // The last word of :define
+            Some(("define", _)) => {
+                let start = line.rfind(' ').unwrap() + 1;
+                Ok((start, self.complete_word(&line[start..]).cloned().collect()))
+            }
+            Some(_) => Ok((pos, vec![])),
+        }
+    }
+}
+
+impl Highlighter for GameHelper {
+    /// Color a guess in red once it is typed in full, if it can't be made
+    fn highlight<'l>(&self, line: &'l str, _: usize) -> Cow<'l, str> {
+        let Some(ref game) = self.game else {
+            return Cow::Borrowed(line);
+        };
+        let word = line.trim();
+        let length = language::count_letters(word);
+        if word.starts_with(PREFIX) || length < WORD_LENGTH {
+            return Cow::Borrowed(line);
+        }
+        let word = game.get_language().to_upper(word);
+        let valid = length == WORD_LENGTH
+            && game
+                .validate_guess(game.is_difficult(), false, &word, &self.word_list)
+                .is_ok();
+        if valid {
+            Cow::Borrowed(line)
+        } else {
+            Cow::Owned(console::style(line).red().to_string())
+        }
+    }
+
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(&'s self, prompt: &'p str, _: bool) -> Cow<'b, str> {
+        Cow::Owned(console::style(prompt).blue().to_string())
+    }
+
+    /// Guesses are checked again as each letter is typed
+    fn highlight_char(&self, _: &str, _: usize, _: bool) -> bool {
+        true
+    }
+}
+
+impl Hinter for GameHelper {
+    type Hint = String;
+}
+
+impl Validator for GameHelper {}
+
+impl Helper for GameHelper {}
+
+/// Line editor of the CLI in tty mode, with history of the lines entered,
+/// completion by Tab, and guesses colored as they are typed
+pub struct Editor {
+    editor: rustyline::Editor<GameHelper, DefaultHistory>,
+}
+
+impl Editor {
+    /// Create an editor on the terminal, if it can be
+    pub fn new(word_list: &[String]) -> Option<Self> {
+        let mut editor = rustyline::Editor::new().ok()?;
+        editor.set_helper(Some(GameHelper {
+            word_list: word_list.to_vec(),
+            game: None,
+        }));
+        Some(Self { editor })
+    }
+
+    /// Read a line of the game, returning None at EOF or when interrupted by Ctrl-C
+    pub fn read_line(&mut self, prompt: &str, game: &Game) -> Option<String> {
+        if let Some(helper) = self.editor.helper_mut() {
+            helper.game = Some(game.clone());
+        }
+        let line = self.editor.readline(prompt).ok()?.trim().to_string();
+        if !line.is_empty() {
+            let _ = self.editor.add_history_entry(&line);
+        }
+        Some(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rustyline::history::MemHistory;
+
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// A helper for a game of CRANE, with some guesses made
+    fn helper(guesses: &[&str]) -> GameHelper {
+        let word_list = words(&["CHASE", "CLAVE", "CRANE", "CRATE", "SLATE", "TIGER"]);
+        let mut game = Game::new("CRANE", false, false, &word_list, language::get("en")).unwrap();
+        for guess in guesses {
+            game.guess(&guess.to_string(), &word_list).unwrap();
+        }
+        GameHelper {
+            word_list,
+            game: Some(game),
+        }
+    }
+
+    fn complete(helper: &GameHelper, line: &str) -> (usize, Vec<String>) {
+        let history = MemHistory::new();
+        helper
+            .complete(line, line.len(), &Context::new(&history))
+            .unwrap()
+    }
+
+    #[test]
+    fn commands_completed() {
+        let helper = helper(&[]);
+        assert_eq!(complete(&helper, ":h"), (0, words(&[":hint ", ":help "])));
+        assert_eq!(complete(&helper, ":gi"), (0, words(&[":giveup "])));
+        assert_eq!(complete(&helper, ":define sl"), (8, words(&["SLATE"])));
+        assert_eq!(complete(&helper, ":undo x"), (7, vec![]));
+    }
+
+    #[test]
+    fn guesses_completed_by_feedback() {
+        let helper = helper(&[]);
+        assert_eq!(complete(&helper, "cra"), (0, words(&["CRANE", "CRATE"])));
+        // S, L and T are known not to be in the answer
+        let helper = self::helper(&["SLATE"]);
+        assert_eq!(complete(&helper, "c"), (0, words(&["CRANE"])));
+        // Nothing is offered before a letter is typed
+        assert_eq!(complete(&helper, ""), (0, vec![]));
+        assert_eq!(complete(&helper, ":define "), (8, vec![]));
+    }
+
+    #[test]
+    fn guesses_highlighted() {
+        let helper = helper(&["SLATE"]);
+        let red = |line| matches!(helper.highlight(line, 0), Cow::Owned(_));
+        // Words can't be told until they are typed in full
+        assert!(!red("xyz"));
+        assert!(!red(":hint"));
+        assert!(!red("crane"));
+        // Unknown words, and those against the feedback in difficult mode only
+        assert!(red("xyzzy"));
+        assert!(!red("tiger"));
+        let mut helper = helper;
+        let word_list = helper.word_list.clone();
+        let mut game = Game::new("CRANE", true, false, &word_list, language::get("en")).unwrap();
+        game.guess(&"SLATE".to_string(), &word_list).unwrap();
+        helper.game = Some(game);
+        assert!(matches!(helper.highlight("tiger", 0), Cow::Owned(_)));
+        assert!(matches!(helper.highlight("chase", 0), Cow::Borrowed(_)));
+    }
+}
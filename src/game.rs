@@ -1,16 +1,18 @@
-use std::collections::HashMap;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
 
 #[cfg(not(target_arch = "wasm32"))]
 use console::Color;
 #[cfg(not(target_arch = "wasm32"))]
 use console::StyledObject;
-use rand::Rng;
+use rand::seq::SliceRandom;
 use serde::Deserialize;
 use serde::Serialize;
 
-const ALPHABET_SIZE: usize = 26;
-const WORD_LENGTH: usize = 5;
-const MAX_GAME_ROUND: usize = 6;
+use crate::language::{self, Language};
+
+pub const WORD_LENGTH: usize = 5;
+pub const MAX_GAME_ROUND: usize = 6;
 
 #[derive(Debug)]
 pub enum Error {
@@ -33,9 +35,20 @@ impl Error {
             Self::HintUnused => String::from("You must use the hint in difficult mode."),
         }
     }
+
+    /// Get an identifier of what happened, for programs to tell errors apart
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get_reason(&self) -> &'static str {
+        match self {
+            Self::UnexpectedWordLength => "unexpected_word_length",
+            Self::UnknownWord => "unknown_word",
+            Self::BadAnswer => "bad_answer",
+            Self::HintUnused => "hint_unused",
+        }
+    }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum LetterStatus {
     Unknown,
     Red,
@@ -45,7 +58,7 @@ pub enum LetterStatus {
 
 impl LetterStatus {
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn to_char(&self) -> char {
+    pub fn to_char(self) -> char {
         match self {
             Self::Unknown => 'X',
             Self::Red => 'R',
@@ -54,10 +67,33 @@ impl LetterStatus {
         }
     }
 
-    // Render letter c with the color of this status
+    /// Get the name of this status, for programs to read
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            Self::Unknown => "unknown",
+            Self::Red => "red",
+            Self::Yellow => "yellow",
+            Self::Green => "green",
+        }
+    }
+
+    /// Get the status of a name, as told by `get_name`
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn colored_char(&self, c: char) -> StyledObject<char> {
-        console::style(c).fg(match self {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "unknown" => Some(Self::Unknown),
+            "red" => Some(Self::Red),
+            "yellow" => Some(Self::Yellow),
+            "green" => Some(Self::Green),
+            _ => None,
+        }
+    }
+
+    // Render a letter with the color of this status
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn colored<'a>(&self, letter: &'a str) -> StyledObject<&'a str> {
+        console::style(letter).fg(match self {
             // Gray
             Self::Unknown => Color::Color256(102),
             Self::Red => Color::Red,
@@ -68,11 +104,27 @@ impl LetterStatus {
 }
 
 pub type GuessStatus = [LetterStatus; WORD_LENGTH];
-pub type Alphabet = [LetterStatus; ALPHABET_SIZE];
+/// Status of each letter of the alphabet of a language, in its order
+pub type Alphabet = Vec<LetterStatus>;
+
+/// Get the names of letter status, as told by `LetterStatus::get_name`
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_names<T: From<&'static str>>(status: &[LetterStatus]) -> Vec<T> {
+    status.iter().map(|status| status.get_name().into()).collect()
+}
 
-// Get the index of a letter in an alphabet
-pub fn get_index(c: char) -> usize {
-    c as usize - 'A' as usize
+/// Map each letter of the alphabet of a language to the name of its status
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get_alphabet_names<T: From<&'static str> + Ord>(
+    language: &Language,
+    alphabet: &[LetterStatus],
+) -> BTreeMap<T, T> {
+    language
+        .alphabet
+        .iter()
+        .map(|&letter| letter.into())
+        .zip(get_names(alphabet))
+        .collect()
 }
 
 #[derive(PartialEq, Eq, Serialize, Deserialize)]
@@ -83,25 +135,92 @@ pub enum GameStatus {
 }
 
 // Auxiliary type and function for counting occurrence of letters
-type Counter = HashMap<char, usize>;
-fn count(counter: &mut Counter, letter: char) -> usize {
+type Counter<'a> = HashMap<&'a str, usize>;
+fn count<'a>(counter: &mut Counter<'a>, letter: &'a str) -> usize {
     *counter
         .entry(letter)
         .and_modify(|cnt| *cnt += 1)
         .or_insert(1)
 }
 
-#[derive(Serialize, Deserialize)]
+/// Get the status of a guess against an answer
+pub fn evaluate(answer: &str, word: &str) -> GuessStatus {
+    // ASCII words are compared byte by byte, since the solver evaluates guesses millions of times
+    if answer.is_ascii() && word.is_ascii() {
+        evaluate_letters(answer.bytes(), word.bytes())
+    } else {
+        evaluate_letters(language::letters(answer), language::letters(word))
+    }
+}
+
+/// Get the status of a guess against an answer, given their letters
+fn evaluate_letters<T: Copy + Default + PartialEq>(
+    answer: impl Iterator<Item = T>,
+    word: impl Iterator<Item = T>,
+) -> GuessStatus {
+    // Letters are kept in arrays instead of counted in maps, for the same reason
+    let mut answer_letters = [T::default(); WORD_LENGTH];
+    answer
+        .zip(answer_letters.iter_mut())
+        .for_each(|(c, letter)| *letter = c);
+    let mut word_letters = [T::default(); WORD_LENGTH];
+    word.zip(word_letters.iter_mut())
+        .for_each(|(c, letter)| *letter = c);
+
+    let mut result = [LetterStatus::Unknown; WORD_LENGTH];
+
+    // Firstly go through the guess to match correct letters
+    // A matched letter in the answer is marked as used, in order that it won't be matched again
+    let mut used = [false; WORD_LENGTH];
+    for (i, &c) in word_letters.iter().enumerate() {
+        if answer_letters[i] == c {
+            used[i] = true;
+            result[i] = LetterStatus::Green;
+        }
+    }
+
+    // Then match other letters with the unused ones in the answer, from left to right
+    for (i, &c) in word_letters.iter().enumerate() {
+        if result[i] == LetterStatus::Green {
+            continue;
+        }
+        result[i] = match (0..WORD_LENGTH).find(|&j| !used[j] && answer_letters[j] == c) {
+            Some(j) => {
+                used[j] = true;
+                LetterStatus::Yellow
+            }
+            None => LetterStatus::Red,
+        };
+    }
+    result
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     answer: String,
     guesses: Vec<(String, GuessStatus)>,
     alphabet: Alphabet,
     difficult: bool,
+    /// Whether accented letters are taken as their base letters when guesses are evaluated
+    #[serde(default)]
+    ignore_accents: bool,
+    /// Name of the language, as games saved by former versions are in English
+    #[serde(default = "language::get_default_name")]
+    language: String,
+    /// Whether the player gave up, which fails the game
+    #[serde(default)]
+    given_up: bool,
 }
 
 impl Game {
-    /// Start a new game with given answer
-    pub fn new(answer: &str, difficult: bool, answer_list: &Vec<String>) -> Result<Self, Error> {
+    /// Start a new game with given answer, in a language
+    pub fn new(
+        answer: &str,
+        difficult: bool,
+        ignore_accents: bool,
+        answer_list: &[String],
+        language: &Language,
+    ) -> Result<Self, Error> {
         // Provided answer not in good answer list
         if !answer_list.contains(&answer.to_string()) {
             return Err(Error::BadAnswer);
@@ -109,8 +228,11 @@ impl Game {
         Ok(Self {
             answer: answer.to_string(),
             guesses: vec![],
-            alphabet: [LetterStatus::Unknown; ALPHABET_SIZE],
+            alphabet: vec![LetterStatus::Unknown; language.get_size()],
             difficult,
+            ignore_accents,
+            language: language.name.to_string(),
+            given_up: false,
         })
     }
 
@@ -119,31 +241,49 @@ impl Game {
         self.guesses.len()
     }
 
+    /// Getter for answer
+    pub fn get_answer(&self) -> &str {
+        &self.answer
+    }
+
     // Getter for guesses
     pub fn get_guesses(&self) -> &Vec<(String, GuessStatus)> {
         &self.guesses
     }
 
+    /// Getter for difficult
+    pub fn is_difficult(&self) -> bool {
+        self.difficult
+    }
+
     /// Setter for difficult
     pub fn set_difficult(&mut self, difficult: bool) {
         self.difficult = difficult;
     }
 
+    /// Setter for ignore_accents
+    pub fn set_ignore_accents(&mut self, ignore_accents: bool) {
+        self.ignore_accents = ignore_accents;
+    }
+
     /// Getter for alphabet
     pub fn get_alphabet(&self) -> &Alphabet {
-        &&self.alphabet
+        &self.alphabet
     }
 
-    /// Get a hint
-    pub fn get_hint(&self, word_list: &Vec<String>) -> String {
-        let mut rng = rand::thread_rng();
-        loop {
-            let index = rng.gen_range(0..word_list.len());
-            let word = &word_list[index];
-            if self.validate_guess(true, true, word, &word_list).is_ok() {
-                break word.to_string()
-            }
-        }
+    /// Get the language of the game
+    pub fn get_language(&self) -> &'static Language {
+        language::get(&self.language)
+    }
+
+    /// Get a hint, a word chosen at random among those fitting every guess so far.
+    /// Return None if no word in the list fits, as every word is checked only once
+    pub fn get_hint(&self, word_list: &[String]) -> Option<String> {
+        let words: Vec<&String> = word_list
+            .iter()
+            .filter(|word| self.validate_guess(true, true, word, word_list).is_ok())
+            .collect();
+        words.choose(&mut rand::thread_rng()).map(|word| word.to_string())
     }
 
     /// Check whether a word can make a valid guess
@@ -156,26 +296,31 @@ impl Game {
         difficult: bool,
         strict: bool,
         word: &String,
-        word_list: &Vec<String>,
+        word_list: &[String],
     ) -> Result<(), Error> {
         // The word list doesn't contains the word
-        if word_list.binary_search(word).is_err() {
-            return Err(Error::UnknownWord);
-        }
+        let word = self.resolve(word, word_list)?;
         // If in difficult mode, do extra checks
         if difficult {
+            let word = self.normalize(word);
+            let word = word.as_ref();
             let mut guess_counter = Counter::new();
-            word.chars().for_each(|c| {
+            language::letters(word).for_each(|c| {
                 count(&mut guess_counter, c);
             });
 
             // Check all guesses because in GUI mode the user may switch between
             // difficult mode and normal mode several times.
             for (guess, status) in &self.guesses {
+                let guess = self.normalize(guess);
+                let guess = guess.as_ref();
                 // Count the occurrence of yellow and green letters for check
                 let mut last_guess_counter = Counter::new();
 
-                for ((i, last_letter), now_letter) in guess.chars().enumerate().zip(word.chars()) {
+                for ((i, last_letter), now_letter) in language::letters(guess)
+                    .enumerate()
+                    .zip(language::letters(word))
+                {
                     match status[i] {
                         LetterStatus::Green => {
                             // Green letters must stay green
@@ -199,23 +344,21 @@ impl Game {
                 }
 
                 if strict {
-                    for ((i, last_letter), now_letter) in
-                        guess.chars().enumerate().zip(word.chars())
+                    for ((i, last_letter), now_letter) in language::letters(guess)
+                        .enumerate()
+                        .zip(language::letters(word))
                     {
                         match status[i] {
                             // Strict rule 1: the count of red letters should be equal
-                            LetterStatus::Red => {
-                                if guess_counter.get(&last_letter).unwrap_or(&0)
-                                    != last_guess_counter.get(&last_letter).unwrap_or(&0)
-                                {
-                                    return Err(Error::HintUnused);
-                                }
+                            LetterStatus::Red
+                                if guess_counter.get(last_letter).unwrap_or(&0)
+                                    != last_guess_counter.get(last_letter).unwrap_or(&0) =>
+                            {
+                                return Err(Error::HintUnused);
                             }
                             // Strict rule 2: yellow letters shouldn't appear in the same position
-                            LetterStatus::Yellow => {
-                                if now_letter == last_letter {
-                                    return Err(Error::HintUnused);
-                                }
+                            LetterStatus::Yellow if now_letter == last_letter => {
+                                return Err(Error::HintUnused);
                             }
                             _ => (),
                         }
@@ -226,64 +369,84 @@ impl Game {
         Ok(())
     }
 
+    /// Find a word in the word list, which may be typed without its accents
+    fn resolve<'a>(&self, word: &'a String, word_list: &'a [String]) -> Result<&'a String, Error> {
+        // Words spelled the same are searched first, as most guesses are
+        match word_list.binary_search(word) {
+            Ok(_) => Ok(word),
+            Err(_) => self
+                .get_language()
+                .find_word(word, word_list)
+                .ok_or(Error::UnknownWord),
+        }
+    }
+
+    /// Get the letters of a word as compared by the rules, without accents if they are ignored
+    fn normalize<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        if self.ignore_accents {
+            self.get_language().fold(word)
+        } else {
+            Cow::Borrowed(word)
+        }
+    }
+
     /// Get the status of a guess
     fn get_guess_status(&self, word: &str) -> GuessStatus {
-        // Count occurrence of letters in the answer
-        let mut ans_counter = Counter::new();
-        self.answer.chars().for_each(|c| {
-            count(&mut ans_counter, c);
-        });
-
-        let mut result = [LetterStatus::Unknown; WORD_LENGTH];
-
-        // Firstly go through the guess to match correct letters
-        let mut visited = [false; 5];
-        for (i, c) in word.chars().enumerate() {
-            if self.answer.chars().nth(i).unwrap() == c {
-                visited[i] = true;
-                // This letter is matched, so decrement the count in answer counter
-                // is order that it won't be matched again
-                *ans_counter.get_mut(&c).unwrap() -= 1;
+        evaluate(&self.normalize(&self.answer), &self.normalize(word))
+    }
+
+    /// Update the alphabet based on the result of a guess
+    fn update_alphabet(&mut self, word: &str, status: &GuessStatus) {
+        let language = self.get_language();
+        for (i, c) in language::letters(word).enumerate() {
+            // Update the state of the letters in the word, if they are in the alphabet
+            if let Some(index) = language.get_index(c) {
+                self.alphabet[index] = self.alphabet[index].max(status[i]);
             }
         }
+    }
 
-        let mut guess_counter = Counter::new();
-        word.chars().enumerate().for_each(|(i, c)| {
-            // Increment the occurrence count of current letter, and compare it with the one in answer
-            result[i] = if visited[i] {
-                LetterStatus::Green
-            } else if count(&mut guess_counter, c) <= *ans_counter.get(&c).unwrap_or(&0) {
-                LetterStatus::Yellow
-            } else {
-                LetterStatus::Red
-            };
-        });
-        result
+    /// Whether the game is over, either won or failed
+    pub fn is_over(&self) -> bool {
+        self.given_up
+            || self.get_round() == MAX_GAME_ROUND
+            || self.guesses.last().is_some_and(|(_, status)| {
+                status.iter().all(|&letter| letter == LetterStatus::Green)
+            })
     }
 
-    /// Update the alphabet based on the result of a guess
-    fn update_alphabet(&mut self, word: &str, status: &GuessStatus) {
-        for (i, c) in word.chars().enumerate() {
-            let index = get_index(c);
-            // Update the state of the letters in the word
-            self.alphabet[index] = self.alphabet[index].max(status[i]);
+    /// Give up the game, which fails it
+    pub fn give_up(&mut self) -> GameStatus {
+        self.given_up = true;
+        GameStatus::Failed(self.answer.to_string())
+    }
+
+    /// Take back the last guess, with the alphabet as it was before
+    pub fn undo(&mut self) -> Option<(String, GuessStatus)> {
+        let last = self.guesses.pop()?;
+        self.alphabet = vec![LetterStatus::Unknown; self.get_language().get_size()];
+        for (word, status) in self.guesses.clone() {
+            self.update_alphabet(&word, &status);
         }
+        Some(last)
     }
 
     /// Make a guess
-    pub fn guess(&mut self, word: &String, word_list: &Vec<String>) -> Result<GameStatus, Error> {
-        if word.len() != WORD_LENGTH {
+    pub fn guess(&mut self, word: &String, word_list: &[String]) -> Result<GameStatus, Error> {
+        if language::count_letters(word) != WORD_LENGTH {
             return Err(Error::UnexpectedWordLength);
         }
 
         // Guess validation
         self.validate_guess(self.difficult, false, word, word_list)?;
+        // The guess is kept as spelled in the word list
+        let word = self.resolve(word, word_list)?;
 
         let guess_status = self.get_guess_status(word);
         self.update_alphabet(word, &guess_status);
         self.guesses.push((word.to_string(), guess_status));
 
-        const COMPLETE_STATUS: GuessStatus = [LetterStatus::Green; 5];
+        const COMPLETE_STATUS: GuessStatus = [LetterStatus::Green; WORD_LENGTH];
 
         let round = self.get_round();
         let game_status = if guess_status == COMPLETE_STATUS {
@@ -297,3 +460,30 @@ impl Game {
         Ok(game_status)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn hint_fits_guesses() {
+        let word_list = words(&["CRANE", "SLATE", "GRACE"]);
+        let mut game = Game::new("CRANE", false, false, &word_list, language::get("en")).unwrap();
+        game.guess(&"SLATE".to_string(), &word_list).unwrap();
+        let hint = game.get_hint(&word_list).unwrap();
+        assert!(hint == "CRANE" || hint == "GRACE");
+    }
+
+    #[test]
+    fn no_hint_left() {
+        let word_list = words(&["CRANE", "SLATE"]);
+        let mut game = Game::new("CRANE", false, false, &word_list, language::get("en")).unwrap();
+        game.guess(&"SLATE".to_string(), &word_list).unwrap();
+        // Only a word used up is left, once the answer is out of the list
+        assert_eq!(game.get_hint(&word_list[1..]), None);
+    }
+}
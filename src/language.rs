@@ -0,0 +1,132 @@
+use std::borrow::Cow;
+
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::builtin_languages;
+
+/// Name of the language played by default
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+/// A language the game can be played in
+pub struct Language {
+    /// Identifier of the language, as given by `--language`
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Letters in upper case, in alphabetical order
+    pub alphabet: &'static [&'static str],
+    /// Rows of letters on the keyboard, from top to bottom
+    pub keyboard: &'static [&'static str],
+    /// Letters whose upper case differs from the Unicode one, such as 'ß' which is kept as is
+    pub uppercase: &'static [(&'static str, &'static str)],
+    pub acceptable: &'static [&'static str],
+    pub final_words: &'static [&'static str],
+}
+
+impl Language {
+    /// Convert a word to upper case, letter by letter
+    pub fn to_upper(&self, word: &str) -> String {
+        // Splitting words into letters is slow, while letters of exceptions are never ASCII
+        if word.is_ascii() {
+            return word.to_ascii_uppercase();
+        }
+        // Letters typed with combining accents are composed, as they are spelled in word lists
+        let word: String = word.nfc().collect();
+        letters(&word)
+            .map(|letter| {
+                match self.uppercase.iter().find(|(from, _)| *from == letter) {
+                    Some((_, to)) => to.to_string(),
+                    None => letter.to_uppercase(),
+                }
+            })
+            .collect()
+    }
+
+    /// Get how many letters there are in the alphabet
+    pub fn get_size(&self) -> usize {
+        self.alphabet.len()
+    }
+
+    /// Get the index of an upper case letter in the alphabet,
+    /// where accented letters take the place of their base letters
+    pub fn get_index(&self, letter: &str) -> Option<usize> {
+        let position = |letter: &str| self.alphabet.iter().position(|&c| c == letter);
+        position(letter).or_else(|| position(&self.fold_letter(letter)))
+    }
+
+    /// Drop the accents of an upper case letter, unless the accented letter is in the alphabet
+    /// on its own, such as 'Ñ' in Spanish. Letters without a base letter in the alphabet are kept
+    pub fn fold_letter<'a>(&self, letter: &'a str) -> Cow<'a, str> {
+        if letter.is_ascii() || self.alphabet.contains(&letter) {
+            return Cow::Borrowed(letter);
+        }
+        let composed: String = letter.nfc().collect();
+        if self.alphabet.contains(&composed.as_str()) {
+            return Cow::Owned(composed);
+        }
+        let base: String = letter.nfd().filter(|&c| !is_combining_mark(c)).collect();
+        if self.alphabet.contains(&base.as_str()) {
+            Cow::Owned(base)
+        } else {
+            Cow::Owned(composed)
+        }
+    }
+
+    /// Drop the accents of a word in upper case, letter by letter
+    pub fn fold<'a>(&self, word: &'a str) -> Cow<'a, str> {
+        if word.is_ascii() {
+            return Cow::Borrowed(word);
+        }
+        Cow::Owned(letters(word).map(|letter| self.fold_letter(letter)).collect())
+    }
+
+    /// Find a word in upper case in a word list, spelled the same,
+    /// or otherwise only differing in accents, such as "ARBOL" for "ÁRBOL"
+    pub fn find_word<'a>(&self, word: &str, word_list: &'a [String]) -> Option<&'a String> {
+        word_list.iter().find(|&entry| entry == word).or_else(|| {
+            let folded = self.fold(word);
+            word_list.iter().find(|entry| self.fold(entry) == folded)
+        })
+    }
+
+    /// Get the builtin acceptable words, in upper case
+    pub fn get_acceptable(&self) -> Vec<String> {
+        self.acceptable.iter().map(|word| self.to_upper(word)).collect()
+    }
+
+    /// Get the builtin final words, in upper case
+    pub fn get_final(&self) -> Vec<String> {
+        self.final_words.iter().map(|word| self.to_upper(word)).collect()
+    }
+}
+
+/// Split a word into letters, where a letter is a grapheme, possibly of several code points
+pub fn letters(word: &str) -> impl Iterator<Item = &str> {
+    word.graphemes(true)
+}
+
+/// Count the letters of a word
+pub fn count_letters(word: &str) -> usize {
+    if word.is_ascii() {
+        word.len()
+    } else {
+        letters(word).count()
+    }
+}
+
+/// Find a language by name
+pub fn find(name: &str) -> Option<&'static Language> {
+    builtin_languages::LANGUAGES
+        .iter()
+        .find(|language| language.name == name)
+}
+
+/// Get a language by name, the default one if unknown
+pub fn get(name: &str) -> &'static Language {
+    find(name).unwrap_or_else(|| find(DEFAULT_LANGUAGE).unwrap())
+}
+
+/// Get the name of the default language, for deserialization
+pub fn get_default_name() -> String {
+    DEFAULT_LANGUAGE.to_string()
+}
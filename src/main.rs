@@ -1,34 +1,67 @@
 #[cfg(not(target_arch = "wasm32"))]
-use clap::Parser;
-#[cfg(not(target_arch = "wasm32"))]
-use console;
-#[cfg(not(target_arch = "wasm32"))]
-use rand::{seq::SliceRandom, SeedableRng};
+use clap::{CommandFactory, Parser};
 #[cfg(not(target_arch = "wasm32"))]
 use std::{
-    collections::HashSet,
-    env,
     io::{self, Write},
     process,
 };
 
-use std::{fs::File, path::PathBuf, io::Read};
+use std::path::{Path, PathBuf};
 
+mod achievements;
+mod analytics;
 mod app;
 mod args;
+mod builtin_languages;
+mod builtin_packs;
 mod builtin_words;
+#[cfg(not(target_arch = "wasm32"))]
+mod config;
 mod dict;
+#[cfg(not(target_arch = "wasm32"))]
+mod editor;
 mod game;
+mod language;
+mod packs;
+#[cfg(not(target_arch = "wasm32"))]
+mod protocol;
+#[cfg(not(target_arch = "wasm32"))]
+mod race;
+#[cfg(not(target_arch = "wasm32"))]
+mod repl;
+#[cfg(not(target_arch = "wasm32"))]
+mod replay;
+#[cfg(not(target_arch = "wasm32"))]
+mod selection;
+#[cfg(not(target_arch = "wasm32"))]
+mod server;
+#[cfg(not(target_arch = "wasm32"))]
+mod solver;
 mod stats;
+#[cfg(not(target_arch = "wasm32"))]
+mod storage;
+#[cfg(not(target_arch = "wasm32"))]
+mod tournament;
+#[cfg(not(target_arch = "wasm32"))]
+mod tui;
+// Word lists can't be loaded in the browser, where only their metadata is used
+#[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+mod words;
 
 use app::WordleApp;
 #[cfg(not(target_arch = "wasm32"))]
-use args::Args;
+use analytics::Analytics;
+#[cfg(not(target_arch = "wasm32"))]
+use args::{Args, Cli, Command, ConfigCommand, GuiArgs, Protocol, Strategy, WordsCommand};
 #[cfg(not(target_arch = "wasm32"))]
-use dict::DICT;
+use words::{Metadata, Severity};
+#[cfg(not(target_arch = "wasm32"))]
+use dict::Dictionary;
 #[cfg(not(target_arch = "wasm32"))]
 use game::{Error, Game, GameStatus, GuessStatus, LetterStatus};
 #[cfg(not(target_arch = "wasm32"))]
+use language::Language;
+#[cfg(not(target_arch = "wasm32"))]
 use stats::Stats;
 
 /// Read a line, trimmed. Return None if EOF encountered
@@ -41,17 +74,6 @@ fn read_line() -> Option<String> {
     }
 }
 
-/// Read a word list from a file
-fn read_word_list(path: &PathBuf) -> Vec<String> {
-    let mut file = File::open(path).unwrap();
-    let mut contents = String::new();
-    file.read_to_string(&mut contents).unwrap();
-    contents
-        .split_whitespace()
-        .map(|s| s.to_uppercase())
-        .collect()
-}
-
 /// Flush the output
 #[cfg(not(target_arch = "wasm32"))]
 fn flush() {
@@ -76,30 +98,54 @@ fn print_status(status: &[LetterStatus]) {
 
 /// Print guess history, in tty mode
 #[cfg(not(target_arch = "wasm32"))]
-fn print_guess_history(guesses: &Vec<(String, GuessStatus)>) {
+fn print_guess_history(guesses: &[(String, GuessStatus)]) {
     for i in 0..6 {
         if i < guesses.len() {
-            for (j, c) in guesses[i].0.chars().enumerate() {
-                print!("{}", guesses[i].1[j].colored_char(c));
+            for (j, c) in language::letters(&guesses[i].0).enumerate() {
+                print!("{}", guesses[i].1[j].colored(c));
             }
-            println!("");
+            println!();
         } else {
             println!("{}", console::style("_____").dim());
         }
     }
 }
 
-/// Print the alphabet, in tty mode
+/// Print the alphabet in the keyboard layout of the language, in tty mode
 #[cfg(not(target_arch = "wasm32"))]
-fn print_alphabet(alphabet: &[LetterStatus]) {
-    const ROW1: &str = "QWERTYUIOP";
-    const ROW2: &str = "ASDFGHJKL";
-    const ROW3: &str = "ZXCVBNM";
-    for row in [ROW1, ROW2, ROW3] {
-        for c in row.chars() {
-            print!("{}", alphabet[game::get_index(c)].colored_char(c));
+fn print_alphabet(alphabet: &[LetterStatus], language: &Language) {
+    for row in language.keyboard {
+        for c in language::letters(row) {
+            // Letters on the keyboard are in the alphabet
+            print!("{}", alphabet[language.get_index(c).unwrap()].colored(c));
         }
-        println!("");
+        println!();
+    }
+}
+
+/// Print the definition of a word in the word list or the dictionary.
+/// Return false if neither defines it
+#[cfg(not(target_arch = "wasm32"))]
+fn print_definition(
+    word: &str,
+    metadata: &Metadata,
+    dictionary: &Dictionary,
+    is_tty: bool,
+) -> bool {
+    let definition = metadata
+        .get(word)
+        .and_then(|entry| entry.definition.as_deref());
+    dict::print_definition(word, definition, dictionary.get(word), is_tty)
+}
+
+/// Tell that a word is not defined, in tty mode
+#[cfg(not(target_arch = "wasm32"))]
+fn print_undefined(is_tty: bool, word: &str) {
+    if is_tty {
+        println!(
+            "{}",
+            console::style(format!("No definition found for {word}")).yellow()
+        );
     }
 }
 
@@ -121,311 +167,844 @@ fn exit_with_error(is_tty: bool, message: &str) -> ! {
     process::exit(1);
 }
 
+/// Run a subcommand instead of a game, on words of a language
+#[cfg(not(target_arch = "wasm32"))]
+fn run_command(is_tty: bool, language: &Language, command: Command) -> ! {
+    match command {
+        Command::ImportState { from, to } => {
+            if storage::is_sqlite(&from) {
+                exit_with_error(is_tty, "Can only import from a JSON state file");
+            }
+            let result = storage::JsonStorage::open(&from).and_then(|from| {
+                let mut to = storage::open(&to)?;
+                storage::import(&from, to.as_mut())
+            });
+            match result {
+                Ok(imported) => {
+                    if is_tty {
+                        println!(
+                            "{}",
+                            console::style(format!(
+                                "Imported {imported} games into {}",
+                                to.display()
+                            ))
+                            .bold()
+                            .green()
+                        );
+                    } else {
+                        println!("{imported}");
+                    }
+                }
+                Err(message) => {
+                    exit_with_error(is_tty, &format!("Failed to import state: {message}"))
+                }
+            }
+        }
+        Command::LintWords {
+            final_set,
+            acceptable_set,
+        } => {
+            let diagnostics =
+                words::lint_lists(acceptable_set.as_deref(), final_set.as_deref(), language);
+            for diagnostic in &diagnostics {
+                if !is_tty {
+                    println!("{diagnostic}");
+                } else if diagnostic.severity == Severity::Error {
+                    println!("{}", console::style(diagnostic).red());
+                } else {
+                    println!("{}", console::style(diagnostic).yellow());
+                }
+            }
+            if is_tty {
+                let errors = diagnostics
+                    .iter()
+                    .filter(|diagnostic| diagnostic.severity == Severity::Error)
+                    .count();
+                println!(
+                    "{}",
+                    console::style(format!(
+                        "{errors} errors, {} warnings",
+                        diagnostics.len() - errors
+                    ))
+                    .bold()
+                );
+            }
+            if words::has_errors(&diagnostics) {
+                process::exit(1);
+            }
+        }
+        Command::Packs { dir } => {
+            let (mut packs, skipped) = match dir {
+                Some(dir) => packs::load_dir(&dir, language).unwrap_or_else(|message| {
+                    exit_with_error(is_tty, &message);
+                }),
+                None => (vec![], vec![]),
+            };
+            // Packs which fail to load are told on stderr, keeping the list on stdout as is
+            for message in skipped {
+                if is_tty {
+                    eprintln!("{}", console::style(format!("Skipped {message}")).yellow());
+                } else {
+                    eprintln!("Skipped {message}");
+                }
+            }
+            packs.extend(packs::builtin());
+            packs::print_packs(&packs, is_tty);
+        }
+        Command::Define { words, dictionary } => {
+            let dictionary = Dictionary::load(&dictionary)
+                .unwrap_or_else(|message| exit_with_error(is_tty, &message));
+            // Every word is looked up, even if some of them are not defined
+            let mut undefined = false;
+            let acceptable = language.get_acceptable();
+            for word in words {
+                // Words are looked up as spelled in the builtin list, which may have accents
+                let word = language.to_upper(&word);
+                let word = language.find_word(&word, &acceptable).unwrap_or(&word);
+                if !dict::print_definition(word, None, dictionary.get(word), is_tty) {
+                    print_undefined(is_tty, word);
+                    undefined = true;
+                }
+            }
+            if undefined {
+                process::exit(1);
+            }
+        }
+        Command::Stats {
+            state,
+            player,
+            extended,
+            compare,
+            filter,
+        } => {
+            let top = filter.get_stats_top();
+            let filter = filter.get_filter();
+            // Other players are compared instead
+            if compare {
+                compare_players(is_tty, &state, &filter);
+                process::exit(0);
+            }
+            let stats = Stats::new(&Some(state), &player)
+                .unwrap_or_else(|| exit_with_error(is_tty, "Failed to load stats"));
+            let filtered = stats.filtered(&filter);
+            let stats = filtered.as_ref().unwrap_or(&stats);
+            stats.print(is_tty, top);
+            if extended {
+                // Only builtin packs are found, and games recorded without their answer lists
+                // are counted against the builtin one
+                let games = stats.get_games();
+                let answer_lists = analytics::load_answer_lists(games, language, None);
+                let analytics = Analytics::new(
+                    games,
+                    &answer_lists,
+                    &language.get_final(),
+                    &Metadata::new(),
+                    top,
+                );
+                analytics::print(&analytics, is_tty);
+            }
+        }
+        Command::Solve {
+            feedback,
+            final_set,
+        } => {
+            let answer_list = match final_set {
+                Some(path) => words::read_entries(&path, language)
+                    .map(|entries| words::get_answers(&entries))
+                    .unwrap_or_else(|message| exit_with_error(is_tty, &message)),
+                None => language.get_final(),
+            };
+            // Guesses may be given without their accents, as typed in games
+            let feedback: Vec<(String, GuessStatus)> = feedback
+                .into_iter()
+                .map(|(guess, status)| (language.to_upper(&guess), status))
+                .collect();
+            let candidates = solver::get_candidates(&answer_list, &feedback);
+            if candidates.is_empty() {
+                exit_with_error(is_tty, "No answer fits the feedback");
+            }
+            let guess = solver::best_guess(&candidates);
+            if is_tty {
+                println!(
+                    "{} answers left, try {}",
+                    candidates.len(),
+                    console::style(guess).bold().green()
+                );
+            } else {
+                println!("{guess} {}", candidates.len());
+            }
+        }
+        Command::Words { command } => run_words_command(is_tty, language, command),
+        Command::Config {
+            command: ConfigCommand::Show,
+        } => load_settings(is_tty).print(is_tty),
+        // Games are played and hosted after the word lists are loaded
+        Command::Play(_)
+        | Command::Gui(_)
+        | Command::Serve { .. }
+        | Command::Race { .. }
+        | Command::Tournament { .. } => {
+            unreachable!()
+        }
+    }
+    process::exit(0);
+}
+
+/// Run a subcommand on word lists of a language
+#[cfg(not(target_arch = "wasm32"))]
+fn run_words_command(is_tty: bool, language: &Language, command: WordsCommand) {
+    // Word lists are checked before used, and the first broken one stops the command
+    let load = |path: &PathBuf| match words::load(path, language) {
+        Ok(list) => list,
+        Err(message) => exit_with_error(is_tty, &message),
+    };
+
+    match command {
+        WordsCommand::Diff { left, right } => {
+            let diff = words::diff(&load(&left), &load(&right));
+            words::print_diff(&diff, &left, &right, is_tty);
+        }
+        WordsCommand::Merge { lists } => {
+            let lists: Vec<Vec<String>> = lists.iter().map(load).collect();
+            words::print_words(&words::merge(&lists));
+        }
+        WordsCommand::SubtractBuiltin { list } => {
+            words::print_words(&words::subtract_builtin(&load(&list), language));
+        }
+        WordsCommand::Stats { list } => {
+            // Duplicates are counted only once
+            let list = words::merge(&[load(&list)]);
+            words::print_letter_frequency(
+                &words::letter_frequency(&list, language),
+                list.len(),
+                is_tty,
+            );
+        }
+    }
+}
+
 /// The main function for the Wordle game, for native run
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
     let is_tty = atty::is(atty::Stream::Stdout);
 
-    let mut args = Args::parse();
+    let Cli { args, gui, command } = Cli::parse();
 
-    // Start GUI
-    if args.gui {
-        eframe::run_native(
-            "Wordle",
-            eframe::NativeOptions::default(),
-            Box::new(|cc| Box::new(WordleApp::new(cc))),
-        );
-        return;
+    // Subcommands with options of their own take none of games before them,
+    // except the language which every subcommand takes
+    if let Some(Command::Play(_) | Command::Gui(_) | Command::Stats { .. } | Command::Solve { .. }) =
+        command
+    {
+        let language = Args {
+            language: args.language.clone(),
+            ..Args::default()
+        };
+        if gui || args != language {
+            exit_with_error(is_tty, "Options of games should go after the subcommand");
+        }
     }
 
-    // Config file specified
-    if let Some(path) = args.config {
-        // Load config file
-        if let Ok(mut defaults) = Args::load_defaults(&path) {
-            // Override config file with command line args
-            defaults.update_from(env::args());
-            args = defaults;
-        } else {
-            exit_with_error(is_tty, "Failed to load config file");
-        }
+    match command {
+        None if gui => run_gui(is_tty, &args, args.get_gui_args()),
+        Some(Command::Gui(gui)) => run_gui(is_tty, &args, gui),
+        // Hosting games needs the word lists as games do
+        None
+        | Some(
+            Command::Play(_) | Command::Serve { .. } | Command::Race { .. } | Command::Tournament { .. },
+        ) => play(is_tty, command),
+        Some(command) => run_command(is_tty, args.get_language(), command),
     }
+}
+
+/// Start the GUI, which takes the rest of its options in its settings
+#[cfg(not(target_arch = "wasm32"))]
+fn run_gui(is_tty: bool, args: &Args, gui: GuiArgs) -> ! {
+    let dictionary =
+        Dictionary::load(&gui.dictionary).unwrap_or_else(|message| exit_with_error(is_tty, &message));
+    // Join a race instead of playing alone
+    let race = gui.join.as_ref().map(|address| {
+        app::Race::join(address, gui.player.clone(), args.get_language())
+            .unwrap_or_else(|message| exit_with_error(is_tty, &message))
+    });
+    // Or play back a race
+    let replay = gui.replay.as_ref().map(|path| {
+        replay::load(path).unwrap_or_else(|message| exit_with_error(is_tty, &message))
+    });
+    eframe::run_native(
+        "Wordle",
+        eframe::NativeOptions::default(),
+        Box::new(|cc| {
+            let mut app = WordleApp::new(cc, dictionary);
+            if let Some(race) = race {
+                app.join(race);
+            }
+            if let Some(replay) = replay {
+                app.play(replay);
+            }
+            Box::new(app)
+        }),
+    );
+    process::exit(0);
+}
+
+/// Word lists and the dictionary, as loaded for games
+#[cfg(not(target_arch = "wasm32"))]
+struct Lists {
+    /// Acceptable words, sorted to accelerate search
+    word_list: Vec<String>,
+    answer_list: Vec<String>,
+    metadata: Metadata,
+    dictionary: Dictionary,
+}
 
+/// Load the word lists given by arguments, or the builtin ones
+#[cfg(not(target_arch = "wasm32"))]
+fn load_lists(is_tty: bool, args: &Args) -> Lists {
     // Validate word list first because we need it for validating other arguments
-    if let Err(message) = args.validate_word_list() {
-        exit_with_error(is_tty, &message);
+    match args.validate_word_list() {
+        Ok(warnings) => {
+            if is_tty {
+                for warning in warnings {
+                    println!("{}", console::style(warning).yellow());
+                }
+            }
+        }
+        Err(message) => exit_with_error(is_tty, &message),
     }
 
-    // Current day
-    let mut day = args.day.unwrap_or(args::DEFAULT_DAY) - 1;
+    let language = args.get_language();
+
+    // Puzzle pack, which takes the place of word lists
+    let pack = args.pack.as_ref().map(|name| {
+        packs::find(name, args.pack_dir.as_deref(), language)
+            .unwrap_or_else(|message| exit_with_error(is_tty, &message))
+    });
 
     // Fetch acceptable words list
-    let mut word_list: Vec<String> = if let Some(ref path) = args.acceptable_set {
-        read_word_list(&path)
+    let acceptable_entries = args
+        .acceptable_set
+        .as_ref()
+        .map(|path| words::read_entries(path, language).unwrap());
+    let mut word_list: Vec<String> = if let Some(ref pack) = pack {
+        pack.get_acceptable(language)
+    } else if let Some(ref entries) = acceptable_entries {
+        entries.iter().map(|entry| entry.word.clone()).collect()
     } else {
-        builtin_words::ACCEPTABLE
-            .iter()
-            .map(|s| s.to_uppercase())
-            .collect()
+        language.get_acceptable()
     };
 
     // Sort the word list to accelerate search
     word_list.sort();
 
     // Fetch final words list
-    let answer_list = {
-        let mut list: Vec<String> = if let Some(ref path) = args.final_set {
-            read_word_list(&path)
+    let final_entries = args
+        .final_set
+        .as_ref()
+        .map(|path| words::read_entries(path, language).unwrap());
+
+    // Dictionary, along with supplementary ones
+    let dictionary = Dictionary::load(&args.dictionary)
+        .unwrap_or_else(|message| exit_with_error(is_tty, &message));
+
+    // Metadata of words, where the final list takes precedence
+    let metadata = words::get_metadata(acceptable_entries.iter().chain(&final_entries).flatten());
+
+    // Retired words are left out of answers
+    let answer_list: Vec<String> = if let Some(ref pack) = pack {
+        pack.get_answers(language)
+    } else if let Some(ref entries) = final_entries {
+        words::get_answers(entries)
+    } else {
+        // If final words list not provided but acceptable list provided,
+        // use the acceptable list as final words list
+        if let Some(ref entries) = acceptable_entries {
+            let mut list = words::get_answers(entries);
+            list.sort();
+            list
         } else {
-            // If final words list not provided but acceptable list provided,
-            // use the acceptable list as final words list
-            if let Some(_) = args.acceptable_set {
-                word_list.clone()
-            } else {
-                builtin_words::FINAL
-                    .iter()
-                    .map(|s| s.to_uppercase())
-                    .collect()
-            }
-        };
-
-        // Ensure the final words are a subset of acceptable words
-        let final_set: HashSet<_> = list.iter().cloned().collect();
-        let acceptable_set: HashSet<_> = word_list.iter().cloned().collect();
-        if !final_set.is_subset(&acceptable_set) {
-            exit_with_error(
-                is_tty,
-                "Final words should be a subset of acceptable words!",
-            )
+            language.get_final()
         }
+    };
 
-        // When in random mode, shuffle the word list
-        if args.random {
-            let mut rng =
-                rand::rngs::StdRng::seed_from_u64(args.seed.unwrap_or(args::DEFAULT_SEED));
-            list.shuffle(&mut rng);
-        }
-        list
+    Lists {
+        word_list,
+        answer_list,
+        metadata,
+        dictionary,
+    }
+}
+
+/// When in random mode, order the answer list by the strategy
+#[cfg(not(target_arch = "wasm32"))]
+fn order_answers(args: &Args, answer_list: Vec<String>, metadata: &Metadata) -> Vec<String> {
+    if !args.random {
+        return answer_list;
+    }
+    selection::order(
+        answer_list,
+        args.strategy.unwrap_or(Strategy::Uniform),
+        args.seed.unwrap_or(args::DEFAULT_SEED),
+        metadata,
+        args.target_difficulty.unwrap_or_default(),
+    )
+}
+
+/// Resolve the settings of games, which are given after `play` or before other subcommands
+#[cfg(not(target_arch = "wasm32"))]
+fn load_settings(is_tty: bool) -> config::Settings {
+    let matches = Cli::command().get_matches();
+    let matches = match matches.subcommand() {
+        Some(("play", matches)) => matches,
+        _ => &matches,
     };
+    config::load(matches).unwrap_or_else(|message| exit_with_error(is_tty, &message))
+}
+
+/// Play games, or host them, with the word lists given
+#[cfg(not(target_arch = "wasm32"))]
+fn play(is_tty: bool, command: Option<Command>) -> ! {
+    let host = command.filter(|command| !matches!(command, Command::Play(_)));
+
+    // Flags override config files and environment variables
+    let mut args = load_settings(is_tty).args;
+
+    // Join a race, where the host has the word lists
+    if let Some(ref address) = args.join {
+        if let Err(message) = race::join(&args, address, is_tty) {
+            exit_with_error(is_tty, &message);
+        }
+        process::exit(0);
+    }
+
+    let lists = load_lists(is_tty, &args);
+
+    // Races are random unless the answer is given, so that --seed and --day work alone
+    if matches!(host, Some(Command::Race { .. })) && args.word.is_none() {
+        args.random = true;
+    }
 
     // Argument validation
-    if let Err(message) = args.validate(&answer_list) {
+    if let Err(message) = args.validate(&lists.answer_list) {
         exit_with_error(is_tty, &message);
     };
 
-    // Initiate statistics
-    let mut stats = if let Some(stats) = Stats::new(&args.state) {
-        stats
-    } else {
-        exit_with_error(
-            is_tty,
-            "Failed to load stats: 'state.json' broken\nYou should consider delete it.",
-        );
+    // Host games instead
+    if let Some(command) = host {
+        host_games(is_tty, &args, lists, command);
+    }
+
+    // Play back a replay, whose answer is chosen again from the word lists as loaded
+    if let Some(ref path) = args.replay {
+        let language = args.get_language();
+        let Lists {
+            ref word_list,
+            ref answer_list,
+            ref metadata,
+            ..
+        } = lists;
+        match replay::play(path, language, word_list, answer_list, metadata, is_tty) {
+            Ok(verified) => process::exit(if verified { 0 } else { 1 }),
+            Err(message) => exit_with_error(is_tty, &message),
+        }
+    }
+
+    play_games(is_tty, args, lists);
+}
+
+/// Host games by a subcommand, with the word lists as loaded
+#[cfg(not(target_arch = "wasm32"))]
+fn host_games(is_tty: bool, args: &Args, lists: Lists, command: Command) -> ! {
+    let language = args.get_language();
+    let Lists {
+        word_list,
+        answer_list,
+        metadata,
+        dictionary,
+    } = lists;
+    let result = match command {
+        // Games served are ordered by their own seeds
+        Command::Serve { port } => {
+            server::run(args, language, word_list, answer_list, &metadata, port)
+        }
+        // Rounds are chosen from the word lists as loaded
+        Command::Tournament { file, export } => tournament::load(&file)
+            .and_then(|tournament| {
+                tournament::run(
+                    &tournament,
+                    args,
+                    language,
+                    &word_list,
+                    &answer_list,
+                    &metadata,
+                    &dictionary,
+                    is_tty,
+                )
+            })
+            .and_then(|leaderboard| {
+                leaderboard.print(is_tty);
+                export.map_or(Ok(()), |path| leaderboard.export(&path))
+            }),
+        // Every player guesses the answer of the day
+        Command::Race {
+            port,
+            players,
+            record,
+        } => {
+            let answer_list = order_answers(args, answer_list, &metadata);
+            let day = args.day.unwrap_or(args::DEFAULT_DAY) - 1;
+            let answer = match args.word {
+                // The answer may be given without its accents
+                Some(ref word) => {
+                    language.find_word(&language.to_upper(word), &answer_list).unwrap()
+                }
+                None => &answer_list[day as usize],
+            };
+            race::host(
+                args,
+                language,
+                &word_list,
+                &answer_list,
+                answer,
+                players as usize,
+                port,
+                record.as_deref(),
+            )
+        }
+        _ => unreachable!("not a command hosting games"),
     };
+    if let Err(message) = result {
+        exit_with_error(is_tty, &message);
+    }
+    process::exit(0);
+}
 
-    // Print welcome message
-    if is_tty {
-        println!(
-            "Welcome to {}{}{}{}{}{}!",
-            console::style('W').bold().red(),
-            console::style('o').bold().color256(208),
-            console::style('r').bold().yellow(),
-            console::style('d').bold().green(),
-            console::style('l').bold().blue(),
-            console::style('e').bold().color256(93),
+/// Print the welcome message, and ask for the name of the player if not given
+#[cfg(not(target_arch = "wasm32"))]
+fn welcome(is_tty: bool, args: &mut Args) {
+    println!(
+        "Welcome to {}{}{}{}{}{}!",
+        console::style('W').bold().red(),
+        console::style('o').bold().color256(208),
+        console::style('r').bold().yellow(),
+        console::style('d').bold().green(),
+        console::style('l').bold().blue(),
+        console::style('e').bold().color256(93),
+    );
+
+    println!("Note that you can type ':hint' to get hints in the game,");
+    println!("and ':help' to see the other commands!\n");
+
+    // The name picks the player profile, unless specified by arguments
+    if args.player.is_none() {
+        print!(
+            "{}",
+            console::style("Could I have your name, please? ")
+                .bold()
+                .blue()
         );
+        flush();
+        let line = if let Some(line) = read_line() {
+            line
+        } else {
+            exit_game(is_tty);
+        };
+        let name = line.trim().to_string();
+        if !name.is_empty() {
+            args.player = Some(name);
+        }
+    }
 
-        println!("Note that you can type 'HINT' to get hints in the game!\n");
+    if let Some(ref name) = args.player {
+        println!("Welcome, {}!\n", name);
+    } else {
+        println!("Welcome!\n");
+    }
+}
 
-        let name = {
+/// Start a new game, with the answer given, of the day, or asked for
+#[cfg(not(target_arch = "wasm32"))]
+fn new_game(is_tty: bool, args: &Args, answer_list: &[String], stats: &Stats, day: &mut u32) -> Game {
+    let language = args.get_language();
+    if let Some(answer) = selection::choose_answer(args, None, answer_list, stats, day) {
+        Game::new(&answer, args.difficult, args.ignore_accents, answer_list, language).unwrap()
+    } else {
+        // Did not provide answer
+        if is_tty {
             print!(
                 "{}",
-                console::style("Could I have your name, please? ")
+                console::style("Please choose an answer for the game: ")
                     .bold()
                     .blue()
             );
-            flush();
-            let line = if let Some(line) = read_line() {
-                line
-            } else {
-                exit_game(is_tty);
+            flush()
+        }
+        loop {
+            let answer: String = match read_line() {
+                Some(word) => word,
+                None => exit_game(is_tty),
             };
-            line.trim().to_string()
-        };
+            let answer = selection::find_answer(language, &answer, answer_list);
+            match Game::new(&answer, args.difficult, args.ignore_accents, answer_list, language) {
+                Ok(game) => break game,
+                Err(error) => print_error(is_tty, &error),
+            }
+        }
+    }
+}
 
-        println!("Welcome, {}!\n", name);
+/// Print statistics after a game, and compare them with other players if asked to
+#[cfg(not(target_arch = "wasm32"))]
+fn print_stats(is_tty: bool, args: &Args, stats: &Stats, answer_list: &[String], metadata: &Metadata) {
+    let filter = args.get_filter();
+    let top = args.get_stats_top();
+    if args.stats {
+        let filtered = stats.filtered(&filter);
+        let stats = filtered.as_ref().unwrap_or(stats);
+        stats.print(is_tty, top);
+        if args.extended {
+            let games = stats.get_games();
+            let language = args.get_language();
+            let answer_lists =
+                analytics::load_answer_lists(games, language, args.pack_dir.as_deref());
+            let analytics = Analytics::new(games, &answer_lists, answer_list, metadata, top);
+            analytics::print(&analytics, is_tty);
+        }
     }
 
-    // Game loop
-    loop {
-        // Did not provide answer
-        let mut game = if args.word.is_none() {
-            // Random mode
-            if args.random {
-                Game::new(&answer_list[day as usize], args.difficult, &answer_list).unwrap()
-            } else {
-                if is_tty {
-                    print!(
-                        "{}",
-                        console::style("Please choose an answer for the game: ")
-                            .bold()
-                            .blue()
-                    );
-                    flush()
+    // Compare statistics with other players
+    if args.compare {
+        compare_players(is_tty, args.state.as_ref().unwrap(), &filter);
+    }
+}
+
+/// Compare statistics of all players in a state file
+#[cfg(not(target_arch = "wasm32"))]
+fn compare_players(is_tty: bool, state: &Path, filter: &stats::Filter) {
+    if let Some(players) = Stats::load_players(state) {
+        let players: Vec<_> = players
+            .into_iter()
+            .map(|(name, stats)| match stats.filtered(filter) {
+                Some(filtered) => (name, filtered),
+                None => (name, stats),
+            })
+            .collect();
+        stats::print_comparison(&players, is_tty);
+    }
+}
+
+/// Ask whether to start a new game, exiting if not. Commands are run meanwhile
+#[cfg(not(target_arch = "wasm32"))]
+fn ask_new_game(session: &mut repl::Session) {
+    let is_tty = session.is_tty;
+    if is_tty && session.args.word.is_none() {
+        loop {
+            print!(
+                "Would you like to start a new game? {} ",
+                console::style("[Y/N]").bold().blue()
+            );
+            flush();
+            match read_line() {
+                None => exit_game(is_tty),
+                Some(line) if line.starts_with(repl::PREFIX) => {
+                    repl::run(session, &line);
                 }
-                loop {
-                    let answer: String = match read_line() {
-                        Some(word) => word,
-                        None => exit_game(is_tty),
-                    };
-                    let answer = answer.to_uppercase();
-                    match Game::new(&answer, args.difficult, &answer_list) {
-                        Ok(game) => break game,
-                        Err(error) => print_error(is_tty, &error),
-                    }
+                Some(line) => match line.as_str() {
+                    "Y" | "y" => break println!(),
+                    "N" | "n" => exit_game(is_tty),
+                    _ => continue,
+                },
+            }
+        }
+    } else if !is_tty {
+        loop {
+            match read_line() {
+                Some(line) if line.starts_with(repl::PREFIX) => {
+                    repl::run(session, &line);
                 }
+                // Continue game loop
+                Some(line) if line == "Y" => break,
+                _ => exit_game(is_tty),
             }
-        } else {
-            Game::new(
-                &args.word.as_ref().unwrap().to_uppercase(),
-                args.difficult,
-                &answer_list,
-            )
-            .unwrap()
-        };
+        }
+    } else {
+        exit_game(is_tty);
+    }
+}
 
-        // Another day of playing wordle...
-        // The mod is here to avoid overflow
-        day += 1;
-        day %= answer_list.len() as u32;
+/// Play games in the terminal, one after another
+#[cfg(not(target_arch = "wasm32"))]
+fn play_games(is_tty: bool, mut args: Args, lists: Lists) -> ! {
+    let language = args.get_language();
+    let Lists {
+        word_list,
+        answer_list,
+        metadata,
+        dictionary,
+    } = lists;
+
+    // This is done after validation, since the solver may take a while
+    let answer_list = order_answers(&args, answer_list, &metadata);
 
-        loop {
-            if is_tty {
-                print!(
-                    "{}",
-                    console::style(format!("Guess {}: ", game.get_round() + 1)).blue()
-                );
-                flush();
-            }
+    // Current day
+    let mut day = args.day.unwrap_or(args::DEFAULT_DAY) - 1;
 
-            let word: String = match read_line() {
-                Some(word) => word,
-                None => exit_game(is_tty),
-            };
-            let word = word.to_uppercase();
+    // The full-screen terminal UI has no room for the welcome message
+    if args.tui && !is_tty {
+        exit_with_error(is_tty, "--tui requires a terminal");
+    }
 
-            // Get hint
-            if word == "HINT" {
-                let hint = game.get_hint(&word_list);
-                println!("{}", console::style(hint).bold().blue());
-                continue;
-            }
+    // Bots are not welcomed
+    let protocol = args.protocol.unwrap_or(Protocol::Plain);
 
-            let result = game.guess(&word, &word_list);
-            match result {
-                Ok(game_status) => {
-                    let guesses = game.get_guesses();
-                    let alphabet = game.get_alphabet();
-                    // Print game status
-                    if is_tty {
-                        print_guess_history(guesses);
-                        println!("--------------");
-                        print_alphabet(alphabet);
-                    } else {
-                        print_status(&guesses.last().unwrap().1);
-                        print!(" ");
-                        print_status(alphabet);
-                        println!("");
-                    }
+    // Print welcome message
+    if is_tty && !args.tui && protocol == Protocol::Plain {
+        welcome(is_tty, &mut args);
+    }
 
-                    // If the word is in the dictionary, print its definition
-                    let print_definition = |word| {
-                        if dict::DICT.contains_key(word) {
-                            println!("{}", console::style(format!("{word}:")).bold().blue());
-                            for (i, sense) in DICT.get(word).unwrap().iter().enumerate() {
-                                println!(
-                                    "{}",
-                                    console::style(format!("    {}: {}", i + 1, sense)).green()
-                                );
-                            }
-                        }
-                    };
-
-                    // Handle win / fail
-                    match game_status {
-                        GameStatus::Won(round) => {
-                            stats.win(args.state.is_some(), guesses);
-                            break if is_tty {
-                                println!(
-                                    "{}",
-                                    console::style(format!("You won in {round} guesses!"))
-                                        .bold()
-                                        .magenta()
-                                );
-
-                                print_definition(&guesses.last().unwrap().0);
-                            } else {
-                                println!("CORRECT {round}");
-                            };
-                        }
-                        GameStatus::Failed(answer) => {
-                            stats.fail(args.state.is_some(), guesses, &answer);
-                            break if is_tty {
-                                println!(
-                                    "{}",
-                                    console::style(format!("You lose! The answer is: {}", answer))
-                                        .bold()
-                                        .red()
-                                );
-
-                                print_definition(&answer);
-                            } else {
-                                println!("FAILED {}", answer);
-                            };
-                        }
-                        GameStatus::Going => (),
-                    }
-                }
-                Err(error) => print_error(is_tty, &error),
-            }
-        }
+    // Initiate statistics
+    let mut stats = if let Some(stats) = Stats::new(&args.state, &args.player) {
+        stats
+    } else {
+        exit_with_error(
+            is_tty,
+            "Failed to load stats: 'state.json' broken\nYou should consider delete it.",
+        );
+    };
+    stats.set_mode(args.difficult, &args.get_word_list_name());
 
-        // Print statistics
-        if args.stats {
-            stats.print(is_tty);
+    // Play in the full-screen terminal UI instead
+    if args.tui {
+        if let Err(e) = tui::run(&args, language, &word_list, &answer_list, stats, day) {
+            exit_with_error(is_tty, &format!("Terminal error: {e}"));
         }
+        exit_game(is_tty);
+    }
 
-        // Ask whether to start a new game
-        if is_tty && args.word.is_none() {
-            loop {
-                print!(
-                    "Would you like to start a new game? {} ",
-                    console::style("[Y/N]").bold().blue()
-                );
-                flush();
-                match read_line() {
-                    None => exit_game(is_tty),
-                    Some(line) => match line.as_str() {
-                        "Y" | "y" => break println!(""),
-                        "N" | "n" => exit_game(is_tty),
-                        _ => continue,
-                    },
+    // Play with requests and responses in JSON Lines instead
+    if protocol == Protocol::Jsonl {
+        protocol::run(&args, language, &word_list, &answer_list, stats, day);
+        process::exit(0);
+    }
+
+    // History is kept from game to game
+    let mut editor = if is_tty {
+        editor::Editor::new(&word_list)
+    } else {
+        None
+    };
+
+    // Game loop
+    loop {
+        let mut game = new_game(is_tty, &args, &answer_list, &stats, &mut day);
+
+        // Another day of playing wordle...
+        day = selection::next_day(day, &answer_list);
+
+        // Commands look at the game along with the word lists
+        let mut session = repl::Session {
+            game: &mut game,
+            stats: &mut stats,
+            args: &args,
+            word_list: &word_list,
+            answer_list: &answer_list,
+            metadata: &metadata,
+            dictionary: &dictionary,
+            is_tty,
+            ranked: false,
+            hints: 0,
+        };
+
+        // The game is over once a guess or command ends it
+        let game_status = loop {
+            let line = match editor {
+                // Lines are edited in tty mode, with guesses checked as they are typed
+                Some(ref mut editor) => {
+                    let prompt = format!("Guess {}: ", session.game.get_round() + 1);
+                    editor.read_line(&prompt, session.game)
                 }
-            }
-        } else if !is_tty {
-            match read_line() {
+                None => read_line(),
+            };
+            let line: String = match line {
+                Some(line) => line,
                 None => exit_game(is_tty),
-                Some(line) => {
-                    if line == "Y" {
-                        // Continue game loop
+            };
+
+            // Commands such as :hint, which may end the game by giving up
+            let game_status = if repl::is_command(&line, &word_list) {
+                match repl::run(&mut session, &line) {
+                    Some(game_status) => game_status,
+                    None => continue,
+                }
+            } else {
+                match session.game.guess(&language.to_upper(&line), &word_list) {
+                    Ok(game_status) => {
+                        let guesses = session.game.get_guesses();
+                        let alphabet = session.game.get_alphabet();
+                        // Print game status
+                        if is_tty {
+                            print_guess_history(guesses);
+                            println!("--------------");
+                            print_alphabet(alphabet, language);
+                        } else {
+                            print_status(&guesses.last().unwrap().1);
+                            print!(" ");
+                            print_status(alphabet);
+                            println!();
+                        }
+                        game_status
+                    }
+                    Err(error) => {
+                        print_error(is_tty, &error);
                         continue;
+                    }
+                }
+            };
+
+            // Handle win / fail
+            let guesses = session.game.get_guesses();
+            match &game_status {
+                GameStatus::Won(round) => {
+                    if is_tty {
+                        println!(
+                            "{}",
+                            console::style(format!("You won in {round} guesses!"))
+                                .bold()
+                                .magenta()
+                        );
+
+                        print_definition(&guesses.last().unwrap().0, &metadata, &dictionary, is_tty);
+                    } else {
+                        println!("CORRECT {round}");
+                    }
+                }
+                GameStatus::Failed(answer) => {
+                    if is_tty {
+                        println!(
+                            "{}",
+                            console::style(format!("You lose! The answer is: {}", answer))
+                                .bold()
+                                .red()
+                        );
+
+                        print_definition(answer, &metadata, &dictionary, is_tty);
                     } else {
-                        exit_game(is_tty);
+                        println!("FAILED {}", answer);
                     }
                 }
+                GameStatus::Going => continue,
             }
-        } else {
-            exit_game(is_tty);
-        }
+            break game_status;
+        };
+        let unlocked = session
+            .stats
+            .finish(session.game, &game_status, args.state.is_some())
+            .unwrap_or_else(|message| exit_with_error(is_tty, &message));
+        achievements::print_unlocked(&unlocked, is_tty);
+
+        print_stats(is_tty, &args, session.stats, &answer_list, &metadata);
+
+        // Ask whether to start a new game, where commands can still be run on the game
+        ask_new_game(&mut session);
     }
 }
 
@@ -435,7 +1014,7 @@ fn main() {
     eframe::start_web(
         "canvas",
         eframe::WebOptions::default(),
-        Box::new(|cc| Box::new(WordleApp::new(cc))),
+        Box::new(|cc| Box::new(WordleApp::new(cc, Default::default()))),
     )
     .unwrap();
 }
@@ -0,0 +1,187 @@
+use std::collections::BTreeSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::builtin_packs;
+use crate::language::Language;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::words::{self, Format};
+
+/// A pack shipped with the game
+pub struct BuiltinPack {
+    pub name: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    /// Name of the language of the words
+    pub language: &'static str,
+    pub answers: &'static [&'static str],
+    pub acceptable: Option<&'static [&'static str]>,
+}
+
+/// A named bundle of answers around a theme.
+/// Packs in a directory are JSON files named after the packs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Pack {
+    #[serde(skip)]
+    pub name: String,
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    /// Name of the language of the words. Packs without one may be played in any language
+    /// whose alphabet their words are in
+    #[serde(default)]
+    pub language: Option<String>,
+    pub answers: Vec<String>,
+    /// Words which can be guessed besides the answers, the builtin acceptable list if not given
+    #[serde(default)]
+    pub acceptable: Option<Vec<String>>,
+}
+
+impl Pack {
+    fn from_builtin(pack: &BuiltinPack) -> Self {
+        let upper = |words: &[&str]| words.iter().map(|word| word.to_uppercase()).collect();
+        Self {
+            name: pack.name.to_string(),
+            title: pack.title.to_string(),
+            description: pack.description.to_string(),
+            language: Some(pack.language.to_string()),
+            answers: upper(pack.answers),
+            acceptable: pack.acceptable.map(upper),
+        }
+    }
+
+    /// Check if the pack can be played in a language
+    pub fn check_language(&self, language: &Language) -> Result<(), String> {
+        match self.language {
+            Some(ref name) if name != language.name => Err(format!(
+                "Pack {} is in language {name}, not {}",
+                self.name, language.name
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Get the answers of the pack, in upper case of the language
+    pub fn get_answers(&self, language: &Language) -> Vec<String> {
+        self.answers.iter().map(|word| language.to_upper(word)).collect()
+    }
+
+    /// Get the words which can be guessed, sorted. Answers can always be guessed,
+    /// along with the builtin acceptable words of the language if not given
+    pub fn get_acceptable(&self, language: &Language) -> Vec<String> {
+        let mut acceptable: BTreeSet<String> = match self.acceptable {
+            Some(ref words) => words.iter().map(|word| language.to_upper(word)).collect(),
+            None => language.get_acceptable().into_iter().collect(),
+        };
+        acceptable.extend(self.get_answers(language));
+        acceptable.into_iter().collect()
+    }
+}
+
+/// Get the name of the answer list of a pack, as recorded along with games
+pub fn get_word_list_name(name: &str) -> String {
+    format!("pack:{name}")
+}
+
+/// Get the name of a pack from the name of its answer list, if it is one
+#[cfg(not(target_arch = "wasm32"))]
+pub fn parse_word_list_name(name: &str) -> Option<&str> {
+    name.strip_prefix("pack:")
+}
+
+/// Get the packs shipped with the game
+pub fn builtin() -> Vec<Pack> {
+    builtin_packs::PACKS.iter().map(Pack::from_builtin).collect()
+}
+
+/// Load a pack from a JSON file named after it. Words are checked like word lists of the language
+#[cfg(not(target_arch = "wasm32"))]
+fn load_file(path: &Path, language: &Language) -> Result<Pack, String> {
+    let source = path.display().to_string();
+    let contents = fs::read_to_string(path).map_err(|e| format!("{source}: failed to read: {e}"))?;
+    let mut pack: Pack = serde_json::from_str(&contents).map_err(|e| format!("{source}: {e}"))?;
+    pack.name = path.file_stem().unwrap().to_string_lossy().to_string();
+    pack.check_language(language)?;
+
+    let mut diagnostics = words::lint(
+        &format!("{source} answers"),
+        Format::Text,
+        &pack.answers.join("\n"),
+        None,
+        language,
+    );
+    if let Some(ref acceptable) = pack.acceptable {
+        diagnostics.extend(words::lint(
+            &format!("{source} acceptable"),
+            Format::Text,
+            &acceptable.join("\n"),
+            None,
+            language,
+        ));
+    }
+    if words::has_errors(&diagnostics) {
+        return Err(words::format_errors(&diagnostics));
+    }
+    Ok(pack)
+}
+
+/// Load the packs in a directory, sorted by name.
+/// Packs which fail to load are left out, and the reasons are returned along with the packs
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load_dir(dir: &Path, language: &Language) -> Result<(Vec<Pack>, Vec<String>), String> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read pack directory: {e}"))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+
+    let mut packs = vec![];
+    let mut skipped = vec![];
+    for path in paths {
+        match load_file(&path, language) {
+            Ok(pack) => packs.push(pack),
+            Err(message) => skipped.push(message),
+        }
+    }
+    Ok((packs, skipped))
+}
+
+/// Find a pack by name, in the directory if given before builtin packs.
+/// Only the file of the pack is read, so that other packs in the directory can't break it
+#[cfg(not(target_arch = "wasm32"))]
+pub fn find(name: &str, dir: Option<&Path>, language: &Language) -> Result<Pack, String> {
+    if let Some(path) = dir.map(|dir| dir.join(name).with_extension("json")) {
+        if path.is_file() {
+            return load_file(&path, language);
+        }
+    }
+    let pack = builtin()
+        .into_iter()
+        .find(|pack| pack.name == name)
+        .ok_or_else(|| format!("Unknown pack: {name}"))?;
+    pack.check_language(language)?;
+    Ok(pack)
+}
+
+/// Print packs with their sizes, in tty mode or as plain lines
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print_packs(packs: &[Pack], is_tty: bool) {
+    for pack in packs {
+        if is_tty {
+            println!(
+                "{} {} ({} answers)",
+                console::style(&pack.name).bold().blue(),
+                console::style(&pack.title).bold(),
+                pack.answers.len()
+            );
+            if !pack.description.is_empty() {
+                println!("    {}", pack.description);
+            }
+        } else {
+            println!("{} {}", pack.name, pack.answers.len());
+        }
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::io::{self, BufRead};
+
+use serde::{Deserialize, Serialize};
+
+use crate::args::Args;
+use crate::game::{
+    get_alphabet_names, get_names, Error, Game, GameStatus, MAX_GAME_ROUND, WORD_LENGTH,
+};
+use crate::language::Language;
+use crate::selection;
+use crate::stats::Stats;
+
+/// A request of the JSON Lines protocol, one object per line
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+enum Request {
+    /// Start a new game, giving up the one going if any, which fails it.
+    /// The answer is needed unless given by arguments or chosen randomly
+    NewGame {
+        #[serde(default)]
+        answer: Option<String>,
+    },
+    Guess {
+        word: String,
+    },
+    Hint,
+    Stats,
+    Quit,
+}
+
+/// How often a word is used, in statistics
+#[derive(Serialize)]
+struct WordUsage<'a> {
+    word: &'a str,
+    count: usize,
+}
+
+/// A response of the JSON Lines protocol, one object per line
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Response<'a> {
+    NewGame {
+        language: &'a str,
+        length: usize,
+        max_rounds: usize,
+    },
+    /// Status of a guess, and of each letter of the alphabet after it
+    Feedback {
+        word: &'a str,
+        round: usize,
+        status: Vec<&'static str>,
+        alphabet: BTreeMap<&'a str, &'static str>,
+    },
+    Hint {
+        word: String,
+    },
+    GameOver {
+        won: bool,
+        rounds: usize,
+        answer: &'a str,
+    },
+    Achievement {
+        id: &'static str,
+        name: &'static str,
+    },
+    Stats {
+        wins: i32,
+        fails: i32,
+        average_tries: f64,
+        favorite_words: Vec<WordUsage<'a>>,
+    },
+    Error {
+        reason: &'static str,
+        message: String,
+    },
+}
+
+/// Print a response as a line
+fn send(response: &Response) {
+    println!("{}", serde_json::to_string(response).unwrap());
+}
+
+/// Tell an error of the protocol rather than the game
+fn send_error(reason: &'static str, message: &str) {
+    send(&Response::Error {
+        reason,
+        message: message.to_string(),
+    });
+}
+
+/// Tell an error of the game
+fn send_game_error(error: &Error) {
+    send(&Response::Error {
+        reason: error.get_reason(),
+        message: error.what(),
+    });
+}
+
+/// Tell the end of a game, and keep its result in statistics
+fn finish(args: &Args, stats: &mut Stats, game: &mut Game, game_status: GameStatus) {
+    match game_status {
+        GameStatus::Won(round) => send(&Response::GameOver {
+            won: true,
+            rounds: round,
+            answer: game.get_answer(),
+        }),
+        GameStatus::Failed(ref answer) => send(&Response::GameOver {
+            won: false,
+            rounds: game.get_round(),
+            answer,
+        }),
+        GameStatus::Going => unreachable!(),
+    }
+    match stats.finish(game, &game_status, args.state.is_some()) {
+        Ok(unlocked) => {
+            for achievement in unlocked {
+                send(&Response::Achievement {
+                    id: achievement.get_id(),
+                    name: achievement.get_name(),
+                });
+            }
+        }
+        Err(message) => send_error("broken_state", &message),
+    }
+}
+
+/// Play games driven by JSON requests from stdin, starting from a day, until quit or EOF
+pub fn run(
+    args: &Args,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    mut stats: Stats,
+    mut day: u32,
+) {
+    // The game going, if any
+    let mut game: Option<Game> = None;
+
+    for line in io::stdin().lock().lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                send_error("bad_request", &e.to_string());
+                continue;
+            }
+        };
+
+        match request {
+            Request::NewGame { answer } => {
+                let Some(answer) =
+                    selection::choose_answer(args, answer.as_ref(), answer_list, &stats, &mut day)
+                else {
+                    send_error("missing_answer", "An answer is needed for the game.");
+                    continue;
+                };
+                match Game::new(
+                    &answer,
+                    args.difficult,
+                    args.ignore_accents,
+                    answer_list,
+                    language,
+                ) {
+                    Ok(new_game) => {
+                        // The game going is given up, and counted as failed
+                        if let Some(mut current) = game.replace(new_game) {
+                            let game_status = current.give_up();
+                            finish(args, &mut stats, &mut current, game_status);
+                        }
+                        day = selection::next_day(day, answer_list);
+                        send(&Response::NewGame {
+                            language: language.name,
+                            length: WORD_LENGTH,
+                            max_rounds: MAX_GAME_ROUND,
+                        });
+                    }
+                    Err(error) => send_game_error(&error),
+                }
+            }
+            Request::Guess { word } => {
+                let Some(current) = game.as_mut() else {
+                    send_error("no_game", "No game is going.");
+                    continue;
+                };
+                let word = language.to_upper(&word);
+                let game_status = match current.guess(&word, word_list) {
+                    Ok(game_status) => game_status,
+                    Err(error) => {
+                        send_game_error(&error);
+                        continue;
+                    }
+                };
+
+                let guesses = current.get_guesses();
+                let (word, status) = guesses.last().unwrap();
+                send(&Response::Feedback {
+                    word,
+                    round: current.get_round(),
+                    status: get_names(status),
+                    alphabet: get_alphabet_names(language, current.get_alphabet()),
+                });
+
+                if let GameStatus::Going = game_status {
+                    continue;
+                }
+                finish(args, &mut stats, current, game_status);
+                game = None;
+            }
+            Request::Hint => match game {
+                Some(ref game) => match game.get_hint(word_list) {
+                    Some(word) => {
+                        stats.use_hint();
+                        send(&Response::Hint { word });
+                    }
+                    None => send_error("no_hint", "No word is left for a hint."),
+                },
+                None => send_error("no_game", "No game is going."),
+            },
+            Request::Stats => send(&Response::Stats {
+                wins: stats.get_wins(),
+                fails: stats.get_fails(),
+                average_tries: stats.get_average_tries(),
+                favorite_words: stats
+                    .get_favorite_words(args.get_stats_top())
+                    .into_iter()
+                    .map(|(word, &count)| WordUsage { word, count })
+                    .collect(),
+            }),
+            Request::Quit => break,
+        }
+    }
+}
@@ -0,0 +1,1031 @@
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::args::{self, Args, Strategy};
+use crate::game::{
+    get_alphabet_names, get_names, Alphabet, Game, GameStatus, GuessStatus, LetterStatus,
+    MAX_GAME_ROUND, WORD_LENGTH,
+};
+use crate::language::{self, Language};
+use crate::replay::{self, Entry, Header, Replay, Step};
+
+/// A request of a player to the host, one JSON object per line
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    /// Join the race, named by the host if no name is given
+    Join {
+        #[serde(default)]
+        name: Option<String>,
+    },
+    Guess {
+        word: String,
+    },
+    /// Watch the race instead, seeing every guess along with its letters
+    Spectate,
+}
+
+/// The place of a player when the race is over
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Standing {
+    pub player: String,
+    pub won: bool,
+    pub rounds: usize,
+    /// Time from the start of the race to the end of the game of the player
+    pub time_ms: u64,
+}
+
+impl Standing {
+    /// Tell how the game of the player went
+    pub fn describe(&self) -> String {
+        let seconds = self.time_ms as f64 / 1000.0;
+        if self.won {
+            format!("won in {} guesses, {seconds:.1}s", self.rounds)
+        } else {
+            format!("failed, {seconds:.1}s")
+        }
+    }
+}
+
+/// Sort standings with winners first, by fewest guesses and then least time,
+/// returning the winner if anybody won
+pub fn rank(standings: &mut [Standing]) -> Option<String> {
+    standings.sort_by_key(|standing| {
+        (
+            !standing.won,
+            if standing.won { standing.rounds } else { 0 },
+            standing.time_ms,
+        )
+    });
+    standings
+        .first()
+        .filter(|standing| standing.won)
+        .map(|standing| standing.player.clone())
+}
+
+/// A message of the host to a player, one JSON object per line
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Response {
+    /// Players joined so far, told whenever one joins or leaves before the race
+    Lobby {
+        players: Vec<String>,
+        needed: usize,
+    },
+    /// The race starts, where the player is known by a name among the players
+    Start {
+        name: String,
+        language: String,
+        length: usize,
+        max_rounds: usize,
+        difficult: bool,
+        players: Vec<String>,
+    },
+    /// Status of a guess of the player, and of each letter of the alphabet after it
+    Feedback {
+        word: String,
+        round: usize,
+        status: Vec<String>,
+        alphabet: BTreeMap<String, String>,
+    },
+    /// Status of a guess of an opponent, without its letters
+    Progress {
+        player: String,
+        round: usize,
+        status: Vec<String>,
+    },
+    /// The game of the player is over, while opponents may still be guessing
+    GameOver {
+        won: bool,
+        rounds: usize,
+        answer: String,
+    },
+    /// An opponent left before the game was over
+    Left {
+        player: String,
+    },
+    /// Every game is over, and the winner is the one with fewest guesses and then least time
+    RaceOver {
+        answer: String,
+        winner: Option<String>,
+        standings: Vec<Standing>,
+    },
+    Error {
+        reason: String,
+        message: String,
+    },
+    /// The race has started, told to spectators along with the steps so far
+    Watch {
+        race: Header,
+        steps: Vec<Entry>,
+    },
+    /// A step of the race, told to spectators as it happens
+    Step(Entry),
+}
+
+/// Send a message as a line. Players gone are none of our business
+pub fn send(stream: &mut TcpStream, message: &impl Serialize) {
+    let _ = writeln!(stream, "{}", serde_json::to_string(message).unwrap());
+}
+
+/// Get the status of a guess from the names of letter status
+pub fn get_status(names: &[String]) -> GuessStatus {
+    let mut status = [LetterStatus::Unknown; WORD_LENGTH];
+    for (status, name) in status.iter_mut().zip(names) {
+        *status = LetterStatus::from_name(name).unwrap_or(LetterStatus::Unknown);
+    }
+    status
+}
+
+/// What happens to connections, told to the host by the threads reading them
+enum Event {
+    Connected(usize, TcpStream),
+    Line(usize, String),
+    Closed(usize),
+}
+
+/// A connection to the host, which becomes a player once joined
+struct Racer {
+    stream: TcpStream,
+    name: Option<String>,
+    game: Option<Game>,
+    /// Whether the player won, and when the game was over, once it is
+    finish: Option<(bool, Duration)>,
+}
+
+/// A race hosted, where every player guesses the same answer
+struct Host<'a> {
+    args: &'a Args,
+    language: &'static Language,
+    word_list: &'a [String],
+    answer_list: &'a [String],
+    answer: &'a str,
+    /// How many players the race starts with
+    needed: usize,
+    racers: HashMap<usize, Racer>,
+    /// Connections joined, in the order they joined
+    joined: Vec<usize>,
+    /// Connections watching the race
+    spectators: Vec<usize>,
+    /// When the race started and what it is played with, if it has started
+    start: Option<Instant>,
+    race: Option<Header>,
+    /// Steps of the race so far
+    steps: Vec<Entry>,
+}
+
+impl<'a> Host<'a> {
+    /// Get the names of the players, in the order they joined
+    fn get_players(&self) -> Vec<String> {
+        self.joined
+            .iter()
+            .map(|id| self.racers[id].name.clone().unwrap())
+            .collect()
+    }
+
+    fn tell(&mut self, id: usize, response: &Response) {
+        if let Some(racer) = self.racers.get_mut(&id) {
+            send(&mut racer.stream, response);
+        }
+    }
+
+    /// Tell every player but one, if any
+    fn broadcast(&mut self, response: &Response, except: Option<usize>) {
+        for id in &self.joined {
+            if Some(*id) != except {
+                send(&mut self.racers.get_mut(id).unwrap().stream, response);
+            }
+        }
+    }
+
+    /// Tell every spectator
+    fn show(&mut self, response: &Response) {
+        for id in &self.spectators {
+            if let Some(racer) = self.racers.get_mut(id) {
+                send(&mut racer.stream, response);
+            }
+        }
+    }
+
+    /// Tell everyone who joined or is watching how many players have joined
+    fn show_lobby(&mut self) {
+        let lobby = Response::Lobby {
+            players: self.get_players(),
+            needed: self.needed,
+        };
+        self.broadcast(&lobby, None);
+        self.show(&lobby);
+    }
+
+    /// Record a step of the race, and show it to spectators
+    fn record(&mut self, step: Step) {
+        let entry = Entry {
+            time_ms: self.start.unwrap().elapsed().as_millis() as u64,
+            step,
+        };
+        self.show(&Response::Step(entry.clone()));
+        self.steps.push(entry);
+    }
+
+    fn refuse(&mut self, id: usize, reason: &str, message: &str) {
+        self.tell(
+            id,
+            &Response::Error {
+                reason: reason.to_string(),
+                message: message.to_string(),
+            },
+        );
+    }
+
+    /// Handle an event, returning whether the race is over
+    fn handle(&mut self, event: Event) -> bool {
+        match event {
+            // Connections are taken even after the race has started, for spectators
+            Event::Connected(id, stream) => {
+                self.racers.insert(
+                    id,
+                    Racer {
+                        stream,
+                        name: None,
+                        game: None,
+                        finish: None,
+                    },
+                );
+                false
+            }
+            Event::Line(id, line) => {
+                if line.trim().is_empty() || !self.racers.contains_key(&id) {
+                    return false;
+                }
+                match serde_json::from_str::<Request>(&line) {
+                    Ok(Request::Join { name }) => self.join(id, name),
+                    Ok(Request::Guess { word }) => return self.guess(id, &word),
+                    Ok(Request::Spectate) => self.spectate(id),
+                    Err(e) => self.refuse(id, "bad_request", &e.to_string()),
+                }
+                false
+            }
+            Event::Closed(id) => self.leave(id),
+        }
+    }
+
+    /// Let a connection join the race, which starts once enough players have joined
+    fn join(&mut self, id: usize, name: Option<String>) {
+        if self.racers[&id].name.is_some() {
+            return self.refuse(id, "already_joined", "You have joined the race.");
+        }
+        if self.spectators.contains(&id) {
+            return self.refuse(id, "spectating", "You are watching the race.");
+        }
+        if self.start.is_some() {
+            return self.refuse(id, "race_started", "The race has started.");
+        }
+        let players = self.get_players();
+        let name = match name.map(|name| name.trim().to_string()) {
+            Some(name) if !name.is_empty() => name,
+            // Players not named are numbered
+            _ => (players.len() + 1..)
+                .map(|n| format!("Player {n}"))
+                .find(|name| !players.contains(name))
+                .unwrap(),
+        };
+        if players.contains(&name) {
+            return self.refuse(id, "name_taken", "The name is taken by another player.");
+        }
+        println!("{name} joined");
+        self.racers.get_mut(&id).unwrap().name = Some(name);
+        self.joined.push(id);
+        self.show_lobby();
+
+        if self.joined.len() == self.needed {
+            self.start_race();
+        }
+    }
+
+    /// Let a connection watch the race, telling the steps so far if it has started
+    fn spectate(&mut self, id: usize) {
+        if self.racers[&id].name.is_some() {
+            return self.refuse(id, "already_joined", "You have joined the race.");
+        }
+        if self.spectators.contains(&id) {
+            return self.refuse(id, "spectating", "You are watching the race.");
+        }
+        self.spectators.push(id);
+        let response = match self.race {
+            Some(ref race) => Response::Watch {
+                race: race.clone(),
+                steps: self.steps.clone(),
+            },
+            None => Response::Lobby {
+                players: self.get_players(),
+                needed: self.needed,
+            },
+        };
+        self.tell(id, &response);
+    }
+
+    /// Start a game for every player
+    fn start_race(&mut self) {
+        let players = self.get_players();
+        for id in self.joined.clone() {
+            let racer = self.racers.get_mut(&id).unwrap();
+            // The answer has been checked along with arguments
+            racer.game = Some(
+                Game::new(
+                    self.answer,
+                    self.args.difficult,
+                    self.args.ignore_accents,
+                    self.answer_list,
+                    self.language,
+                )
+                .unwrap(),
+            );
+            let name = racer.name.clone().unwrap();
+            self.tell(
+                id,
+                &Response::Start {
+                    name,
+                    language: self.language.name.to_string(),
+                    length: WORD_LENGTH,
+                    max_rounds: MAX_GAME_ROUND,
+                    difficult: self.args.difficult,
+                    players: players.clone(),
+                },
+            );
+        }
+        self.start = Some(Instant::now());
+        println!("The race has started");
+
+        let args = self.args;
+        // The answer was chosen as in random mode, unless it was given
+        let random = args.word.is_none();
+        let race = Header {
+            language: self.language.name.to_string(),
+            word_list: args.get_word_list_name(),
+            word_list_hash: replay::hash_word_lists(self.word_list, self.answer_list),
+            seed: random.then(|| args.seed.unwrap_or(args::DEFAULT_SEED)),
+            strategy: random.then(|| args.strategy.unwrap_or(Strategy::Uniform)),
+            target_difficulty: args.target_difficulty,
+            day: random.then(|| args.day.unwrap_or(args::DEFAULT_DAY)),
+            difficult: args.difficult,
+            ignore_accents: args.ignore_accents,
+            players,
+        };
+        self.show(&Response::Watch {
+            race: race.clone(),
+            steps: vec![],
+        });
+        self.race = Some(race);
+    }
+
+    /// Make a guess for a player, returning whether the race is over
+    fn guess(&mut self, id: usize, word: &str) -> bool {
+        if self.racers[&id].name.is_none() {
+            self.refuse(id, "not_joined", "Join the race first.");
+            return false;
+        }
+        let Some(start) = self.start else {
+            self.refuse(id, "not_started", "The race has not started yet.");
+            return false;
+        };
+        let racer = self.racers.get_mut(&id).unwrap();
+        if racer.finish.is_some() {
+            self.refuse(id, "game_over", "Your game is over.");
+            return false;
+        }
+
+        let game = racer.game.as_mut().unwrap();
+        let word = self.language.to_upper(word);
+        let game_status = match game.guess(&word, self.word_list) {
+            Ok(game_status) => game_status,
+            Err(error) => {
+                let response = Response::Error {
+                    reason: error.get_reason().to_string(),
+                    message: error.what(),
+                };
+                self.tell(id, &response);
+                return false;
+            }
+        };
+
+        let (word, status) = game.get_guesses().last().unwrap();
+        let feedback = Response::Feedback {
+            word: word.clone(),
+            round: game.get_round(),
+            status: get_names(status),
+            alphabet: get_alphabet_names(self.language, game.get_alphabet()),
+        };
+        let player = racer.name.clone().unwrap();
+        // Opponents see the pattern only, while spectators see the letters as well
+        let progress = Response::Progress {
+            player: player.clone(),
+            round: game.get_round(),
+            status: get_names(status),
+        };
+        let step = Step::Guess {
+            player: player.clone(),
+            word: word.clone(),
+            status: get_names(status),
+        };
+        let game_over = match game_status {
+            GameStatus::Going => None,
+            ref game_status => {
+                let won = matches!(game_status, GameStatus::Won(_));
+                racer.finish = Some((won, start.elapsed()));
+                println!("{player} finished");
+                Some((won, game.get_round(), game.get_answer().to_string()))
+            }
+        };
+
+        self.tell(id, &feedback);
+        self.broadcast(&progress, Some(id));
+        self.record(step);
+        if let Some((won, rounds, answer)) = game_over {
+            self.tell(id, &Response::GameOver { won, rounds, answer });
+            self.record(Step::GameOver { player, won, rounds });
+        }
+        self.finish()
+    }
+
+    /// Let a connection go, returning whether the race is over
+    fn leave(&mut self, id: usize) -> bool {
+        let Some(racer) = self.racers.get_mut(&id) else {
+            return false;
+        };
+        let Some(name) = racer.name.clone() else {
+            self.racers.remove(&id);
+            self.spectators.retain(|spectator| *spectator != id);
+            return false;
+        };
+        let Some(start) = self.start else {
+            println!("{name} left");
+            self.racers.remove(&id);
+            self.joined.retain(|joined| *joined != id);
+            self.show_lobby();
+            return false;
+        };
+        // Players leaving before their games are over fail, and are kept in the standings
+        if racer.finish.is_some() {
+            return false;
+        }
+        println!("{name} left");
+        racer.finish = Some((false, start.elapsed()));
+        self.broadcast(&Response::Left { player: name.clone() }, Some(id));
+        self.record(Step::Left { player: name });
+        self.finish()
+    }
+
+    /// End the race if every game is over, returning whether it is over
+    fn finish(&mut self) -> bool {
+        if self
+            .joined
+            .iter()
+            .any(|id| self.racers[id].finish.is_none())
+        {
+            return false;
+        }
+
+        let mut standings: Vec<Standing> = self
+            .joined
+            .iter()
+            .map(|id| {
+                let racer = &self.racers[id];
+                let (won, time) = racer.finish.unwrap();
+                Standing {
+                    player: racer.name.clone().unwrap(),
+                    won,
+                    rounds: racer.game.as_ref().unwrap().get_round(),
+                    time_ms: time.as_millis() as u64,
+                }
+            })
+            .collect();
+        let winner = rank(&mut standings);
+
+        match winner {
+            Some(ref winner) => println!("{winner} wins!"),
+            None => println!("Nobody wins."),
+        }
+        for (i, standing) in standings.iter().enumerate() {
+            println!("{}. {}: {}", i + 1, standing.player, standing.describe());
+        }
+
+        self.record(Step::RaceOver {
+            answer: self.answer.to_string(),
+            winner: winner.clone(),
+        });
+        self.broadcast(
+            &Response::RaceOver {
+                answer: self.answer.to_string(),
+                winner,
+                standings,
+            },
+            None,
+        );
+        true
+    }
+}
+
+/// Accept connections, and read the requests of each in a thread of its own
+fn accept(listener: TcpListener, sender: Sender<Event>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else { continue };
+        let Ok(reader) = stream.try_clone() else {
+            continue;
+        };
+        if sender.send(Event::Connected(id, stream)).is_err() {
+            return;
+        }
+        let sender = sender.clone();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(Event::Line(id, line)).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(Event::Closed(id));
+        });
+    }
+}
+
+/// Host a race on localhost, where every player guesses the same answer, until it is over.
+/// A replay of the race is saved if a path is given
+#[allow(clippy::too_many_arguments)]
+pub fn host(
+    args: &Args,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    answer: &str,
+    players: usize,
+    port: u16,
+    record: Option<&Path>,
+) -> Result<(), String> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to listen on port {port}: {e}"))?;
+    // The port is told, since it is chosen by the system if 0
+    println!("Listening on {}", listener.local_addr().unwrap());
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || accept(listener, sender));
+
+    let mut host = Host {
+        args,
+        language,
+        word_list,
+        answer_list,
+        answer,
+        needed: players,
+        racers: HashMap::new(),
+        joined: vec![],
+        spectators: vec![],
+        start: None,
+        race: None,
+        steps: vec![],
+    };
+    // Events are handled one at a time, so that every player is told things in the same order
+    for event in receiver {
+        if host.handle(event) {
+            break;
+        }
+    }
+    // The replay is saved before the connections are closed, so that it is there once the race is over
+    let saved = match (record, host.race) {
+        (Some(path), Some(race)) => {
+            let replay = Replay {
+                race,
+                answer: answer.to_string(),
+                steps: host.steps,
+            };
+            replay::save(&replay, path)
+        }
+        _ => Ok(()),
+    };
+    for racer in host.racers.values() {
+        let _ = racer.stream.shutdown(Shutdown::Write);
+    }
+    saved
+}
+
+/// An opponent, as seen by a player
+pub struct Opponent {
+    pub name: String,
+    /// Status of each guess, without its letters
+    pub rows: Vec<GuessStatus>,
+    pub left: bool,
+}
+
+/// What a player knows about a race, as told by the host
+pub struct Board {
+    pub language: &'static Language,
+    /// Players joined so far, before the race starts
+    pub lobby: Vec<String>,
+    pub needed: usize,
+    pub started: bool,
+    pub guesses: Vec<(String, GuessStatus)>,
+    pub alphabet: Alphabet,
+    pub opponents: Vec<Opponent>,
+    /// Whether the player won, and the answer, once the game of the player is over
+    pub over: Option<(bool, String)>,
+    /// Standings of every player, once the race is over
+    pub standings: Option<Vec<Standing>>,
+    /// The last error told by the host, until the next guess is taken
+    pub error: Option<String>,
+}
+
+impl Board {
+    /// A board before the race, in the language expected until the host tells its own
+    pub fn new(language: &'static Language) -> Self {
+        Self {
+            language,
+            lobby: vec![],
+            needed: 0,
+            started: false,
+            guesses: vec![],
+            alphabet: vec![LetterStatus::Unknown; language.get_size()],
+            opponents: vec![],
+            over: None,
+            standings: None,
+            error: None,
+        }
+    }
+
+    fn get_opponent(&mut self, name: &str) -> Option<&mut Opponent> {
+        self.opponents
+            .iter_mut()
+            .find(|opponent| opponent.name == name)
+    }
+
+    /// Get the status of the game of the player
+    pub fn get_game_status(&self) -> GameStatus {
+        match self.over {
+            Some((true, _)) => GameStatus::Won(self.guesses.len()),
+            Some((false, ref answer)) => GameStatus::Failed(answer.clone()),
+            None => GameStatus::Going,
+        }
+    }
+
+    /// Whether the game of the player is over, which is known by the last guess
+    /// even before the host tells
+    pub fn is_over(&self) -> bool {
+        self.over.is_some()
+            || self.guesses.len() == MAX_GAME_ROUND
+            || self.guesses.last().is_some_and(|(_, status)| {
+                status.iter().all(|status| *status == LetterStatus::Green)
+            })
+    }
+
+    /// Get the winner, once the race is over
+    pub fn get_winner(&self) -> Option<&str> {
+        self.standings
+            .as_ref()?
+            .first()
+            .filter(|standing| standing.won)
+            .map(|standing| standing.player.as_str())
+    }
+
+    /// Learn from a message of the host
+    pub fn apply(&mut self, response: &Response) {
+        match response {
+            Response::Lobby { players, needed } => {
+                self.lobby = players.clone();
+                self.needed = *needed;
+            }
+            Response::Start {
+                name,
+                language,
+                players,
+                ..
+            } => {
+                self.language = language::get(language);
+                self.alphabet = vec![LetterStatus::Unknown; self.language.get_size()];
+                self.opponents = players
+                    .iter()
+                    .filter(|player| *player != name)
+                    .map(|player| Opponent {
+                        name: player.clone(),
+                        rows: vec![],
+                        left: false,
+                    })
+                    .collect();
+                self.started = true;
+            }
+            Response::Feedback {
+                word,
+                status,
+                alphabet,
+                ..
+            } => {
+                self.guesses.push((word.clone(), get_status(status)));
+                for (letter, status) in alphabet {
+                    if let Some(index) = self.language.get_index(letter) {
+                        self.alphabet[index] =
+                            LetterStatus::from_name(status).unwrap_or(LetterStatus::Unknown);
+                    }
+                }
+                self.error = None;
+            }
+            Response::Progress { player, status, .. } => {
+                if let Some(opponent) = self.get_opponent(player) {
+                    opponent.rows.push(get_status(status));
+                }
+            }
+            Response::GameOver { won, answer, .. } => self.over = Some((*won, answer.clone())),
+            Response::Left { player } => {
+                if let Some(opponent) = self.get_opponent(player) {
+                    opponent.left = true;
+                }
+            }
+            Response::RaceOver { standings, .. } => self.standings = Some(standings.clone()),
+            Response::Error { message, .. } => self.error = Some(message.clone()),
+            // Only spectators are told every step
+            Response::Watch { .. } | Response::Step(_) => (),
+        }
+    }
+}
+
+/// Connect to a host and join or watch its race. Messages of the host are read in a thread,
+/// and sent wrapped as input, with None once the host has gone
+pub fn connect<T: Send + 'static>(
+    address: &str,
+    request: Request,
+    sender: Sender<T>,
+    wrap: fn(Option<Response>) -> T,
+) -> Result<TcpStream, String> {
+    let mut stream =
+        TcpStream::connect(address).map_err(|e| format!("Failed to connect to {address}: {e}"))?;
+    let reader = stream.try_clone().map_err(|e| e.to_string())?;
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            // Messages not understood are left out
+            let Ok(response) = serde_json::from_str(&line) else {
+                continue;
+            };
+            if sender.send(wrap(Some(response))).is_err() {
+                return;
+            }
+        }
+        let _ = sender.send(wrap(None));
+    });
+    send(&mut stream, &request);
+    Ok(stream)
+}
+
+/// Input of a player racing in the CLI
+enum Input {
+    /// A message of the host, or None once the host has gone
+    Host(Option<Response>),
+    /// A line typed, or None at EOF
+    Line(Option<String>),
+}
+
+/// Prompt for the next guess, in tty mode
+fn prompt(board: &Board) {
+    print!(
+        "{}",
+        console::style(format!("Guess {}: ", board.guesses.len() + 1)).blue()
+    );
+    crate::flush();
+}
+
+/// Print who have joined, in tty mode
+fn print_lobby(players: &[String], needed: usize) {
+    println!(
+        "{}",
+        console::style(format!(
+            "Waiting for players ({}/{needed}): {}",
+            players.len(),
+            players.join(", ")
+        ))
+        .dim()
+    );
+}
+
+/// Print what the host tells
+fn print_response(board: &Board, response: &Response, is_tty: bool) {
+    match response {
+        Response::Lobby { players, needed } => {
+            if is_tty {
+                print_lobby(players, *needed);
+            }
+        }
+        Response::Start { .. } => {
+            if is_tty {
+                println!("{}", console::style("The race is on!").bold().green());
+                prompt(board);
+            }
+        }
+        Response::Feedback { .. } => {
+            if is_tty {
+                crate::print_guess_history(&board.guesses);
+                println!("--------------");
+                crate::print_alphabet(&board.alphabet, board.language);
+                if board.over.is_none() && board.guesses.len() < MAX_GAME_ROUND {
+                    prompt(board);
+                }
+            } else {
+                crate::print_status(&board.guesses.last().unwrap().1);
+                print!(" ");
+                crate::print_status(&board.alphabet);
+                println!();
+            }
+        }
+        Response::Progress { player, status, .. } => {
+            let status = get_status(status);
+            if is_tty {
+                print!("{}: ", console::style(player).bold());
+                for status in status {
+                    print!("{}", status.colored("■"));
+                }
+                println!();
+            } else {
+                print!("{player} ");
+                crate::print_status(&status);
+                println!();
+            }
+        }
+        Response::GameOver {
+            won,
+            rounds,
+            answer,
+        } => {
+            if !is_tty {
+                if *won {
+                    println!("CORRECT {rounds}");
+                } else {
+                    println!("FAILED {answer}");
+                }
+                return;
+            }
+            if *won {
+                println!(
+                    "{}",
+                    console::style(format!("You won in {rounds} guesses!"))
+                        .bold()
+                        .magenta()
+                );
+            } else {
+                println!(
+                    "{}",
+                    console::style(format!("You lose! The answer is: {answer}"))
+                        .bold()
+                        .red()
+                );
+            }
+            println!("{}", console::style("Waiting for other players...").dim());
+        }
+        Response::Left { player } => {
+            if is_tty {
+                println!(
+                    "{}",
+                    console::style(format!("{player} left the race")).yellow()
+                );
+            } else {
+                println!("{player} LEFT");
+            }
+        }
+        Response::RaceOver { .. } => {
+            let winner = board.get_winner();
+            if !is_tty {
+                match winner {
+                    Some(winner) => println!("WINNER {winner}"),
+                    None => println!("NO WINNER"),
+                }
+                return;
+            }
+            match winner {
+                Some(winner) => println!(
+                    "{}",
+                    console::style(format!("{winner} wins!")).bold().magenta()
+                ),
+                None => println!("{}", console::style("Nobody wins.").bold()),
+            }
+            for (i, standing) in board.standings.iter().flatten().enumerate() {
+                println!("{}. {}: {}", i + 1, standing.player, standing.describe());
+            }
+        }
+        Response::Error { message, .. } => {
+            if is_tty {
+                println!("{}", console::style(message).bold().red());
+                if board.started && board.over.is_none() {
+                    prompt(board);
+                }
+            } else {
+                println!("INVALID");
+            }
+        }
+        Response::Watch { .. } | Response::Step(_) => (),
+    }
+}
+
+/// Watch a race from the CLI, from the steps so far until it is over
+fn watch(receiver: Receiver<Input>, is_tty: bool) -> Result<(), String> {
+    for input in receiver {
+        let Input::Host(response) = input else {
+            continue;
+        };
+        let Some(response) = response else {
+            return Err("The host has gone.".to_string());
+        };
+        match response {
+            Response::Lobby { players, needed } if is_tty => print_lobby(&players, needed),
+            Response::Watch { race, steps } => {
+                if is_tty {
+                    replay::print_header(&race);
+                }
+                for entry in steps {
+                    replay::print_step(&entry.step, is_tty);
+                }
+            }
+            Response::Step(entry) => {
+                replay::print_step(&entry.step, is_tty);
+                if let Step::RaceOver { .. } = entry.step {
+                    return Ok(());
+                }
+            }
+            Response::Error { message, .. } => return Err(message),
+            _ => (),
+        }
+    }
+    Ok(())
+}
+
+/// Join a race from the CLI, until it is over or there is nothing more to guess,
+/// or watch it if spectating
+pub fn join(args: &Args, address: &str, is_tty: bool) -> Result<(), String> {
+    let (sender, receiver) = mpsc::channel();
+    if args.spectate {
+        connect(address, Request::Spectate, sender, Input::Host)?;
+        return watch(receiver, is_tty);
+    }
+    let request = Request::Join {
+        name: args.player.clone(),
+    };
+    let mut stream = connect(address, request, sender.clone(), Input::Host)?;
+    // Lines are read in a thread, so that opponents are followed while typing
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else { break };
+            if sender
+                .send(Input::Line(Some(line.trim().to_string())))
+                .is_err()
+            {
+                return;
+            }
+        }
+        let _ = sender.send(Input::Line(None));
+    });
+
+    let mut board = Board::new(args.get_language());
+    // Guesses typed but not sent, since they are sent one at a time once the race starts
+    let mut typed = VecDeque::new();
+    let mut pending = false;
+    let mut eof = false;
+    for input in receiver {
+        match input {
+            Input::Line(Some(line)) => {
+                if !line.is_empty() {
+                    typed.push_back(line);
+                }
+            }
+            Input::Line(None) => eof = true,
+            Input::Host(None) => return Err("The host has gone.".to_string()),
+            Input::Host(Some(response)) => {
+                // Only joining is requested before the race, which fails if refused
+                if let (false, Response::Error { message, .. }) = (board.started, &response) {
+                    return Err(message.clone());
+                }
+                board.apply(&response);
+                print_response(&board, &response, is_tty);
+                match response {
+                    Response::Feedback { .. } | Response::Error { .. } => pending = false,
+                    Response::RaceOver { .. } => return Ok(()),
+                    _ => (),
+                }
+            }
+        }
+
+        if board.is_over() {
+            continue;
+        }
+        if board.started && !pending {
+            if let Some(word) = typed.pop_front() {
+                send(&mut stream, &Request::Guess { word });
+                pending = true;
+            }
+        }
+        // Leave the race if nothing more is to be guessed
+        if eof && typed.is_empty() && !pending {
+            return Ok(());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,289 @@
+use crate::args::Args;
+use crate::dict::Dictionary;
+use crate::game::{Game, GameStatus, LetterStatus, MAX_GAME_ROUND};
+use crate::solver;
+use crate::stats::Stats;
+use crate::words::Metadata;
+
+/// Commands typed in the CLI start with it, so that they are never taken as guesses
+pub const PREFIX: char = ':';
+
+/// How many candidates are listed by `:candidates`
+const CANDIDATES_SHOWN: usize = 10;
+
+/// A game in the CLI, along with what commands may look at
+pub struct Session<'a> {
+    pub game: &'a mut Game,
+    pub stats: &'a mut Stats,
+    pub args: &'a Args,
+    pub word_list: &'a [String],
+    pub answer_list: &'a [String],
+    pub metadata: &'a Metadata,
+    pub dictionary: &'a Dictionary,
+    pub is_tty: bool,
+    /// Whether the game is ranked against other players, as in tournaments,
+    /// where commands which would help unfairly can't be used
+    pub ranked: bool,
+    /// Hints given in the game, which break ties in tournaments
+    pub hints: usize,
+}
+
+/// A command in the CLI, such as `:hint`
+struct Command {
+    name: &'static str,
+    /// Arguments taken, as shown by `:help`
+    usage: &'static str,
+    help: &'static str,
+    /// Whether the command only makes sense while the game is going
+    in_game: bool,
+    /// Whether the command can be used in ranked games, as it doesn't help unfairly
+    fair: bool,
+    /// Run the command with its arguments, returning the status of the game if it is over
+    run: fn(&mut Session, &[&str]) -> Result<Option<GameStatus>, String>,
+}
+
+const COMMANDS: &[Command] = &[
+    Command {
+        name: "hint",
+        usage: "",
+        help: "Suggest a word which fits all the letters revealed",
+        in_game: true,
+        fair: true,
+        run: hint,
+    },
+    Command {
+        name: "undo",
+        usage: "",
+        help: "Take back the last guess",
+        in_game: true,
+        fair: false,
+        run: undo,
+    },
+    Command {
+        name: "candidates",
+        usage: "",
+        help: "List the answers which fit the guesses so far",
+        in_game: false,
+        fair: false,
+        run: candidates,
+    },
+    Command {
+        name: "stats",
+        usage: "",
+        help: "Show statistics of the player",
+        in_game: false,
+        fair: true,
+        run: stats,
+    },
+    Command {
+        name: "define",
+        usage: "WORD...",
+        help: "Look up words in the dictionary",
+        in_game: false,
+        fair: true,
+        run: define,
+    },
+    Command {
+        name: "share",
+        usage: "",
+        help: "Show the result of the game as colored squares, to share with others",
+        in_game: false,
+        fair: true,
+        run: share,
+    },
+    Command {
+        name: "giveup",
+        usage: "",
+        help: "Give up the game, which is counted as failed",
+        in_game: true,
+        fair: true,
+        run: give_up,
+    },
+    Command {
+        name: "help",
+        usage: "",
+        help: "List the commands",
+        in_game: false,
+        fair: true,
+        run: help,
+    },
+    Command {
+        name: "quit",
+        usage: "",
+        help: "Quit the game",
+        in_game: false,
+        fair: true,
+        run: quit,
+    },
+];
+
+/// Names of the commands, without the prefix
+pub fn get_names() -> impl Iterator<Item = &'static str> {
+    COMMANDS.iter().map(|command| command.name)
+}
+
+/// Whether a line is a command rather than a guess. `HINT` and `DEFINE WORD...`,
+/// as typed in former versions, are commands as well, unless HINT is a word in the list
+pub fn is_command(line: &str, word_list: &[String]) -> bool {
+    let line = line.trim().to_uppercase();
+    line.starts_with(PREFIX)
+        || line.split_whitespace().next() == Some("DEFINE")
+        || (line == "HINT" && word_list.binary_search(&line).is_err())
+}
+
+/// Run a command, returning the status of the game if the command ends it
+pub fn run(session: &mut Session, line: &str) -> Option<GameStatus> {
+    let line = line.trim();
+    let mut words = line.strip_prefix(PREFIX).unwrap_or(line).split_whitespace();
+    let name = words.next().unwrap_or_default().to_lowercase();
+    let args: Vec<&str> = words.collect();
+
+    let result = match COMMANDS.iter().find(|command| command.name == name) {
+        None => Err(format!("Unknown command {PREFIX}{name}, type {PREFIX}help to list commands")),
+        Some(command) if command.in_game && session.game.is_over() => {
+            Err(format!("{PREFIX}{name} can only be used while the game is going"))
+        }
+        Some(command) if session.ranked && !command.fair => {
+            Err(format!("{PREFIX}{name} can't be used in ranked games"))
+        }
+        Some(command) => (command.run)(session, &args),
+    };
+    result.unwrap_or_else(|message| {
+        if session.is_tty {
+            println!("{}", console::style(message).bold().red());
+        } else {
+            println!("INVALID");
+        }
+        None
+    })
+}
+
+fn hint(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    let hint = session
+        .game
+        .get_hint(session.word_list)
+        .ok_or_else(|| "No word is left for a hint".to_string())?;
+    session.stats.use_hint();
+    session.hints += 1;
+    println!("{}", console::style(hint).bold().blue());
+    Ok(None)
+}
+
+fn undo(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    let (word, _) = session
+        .game
+        .undo()
+        .ok_or_else(|| "No guess to take back".to_string())?;
+    if session.is_tty {
+        println!("{}", console::style(format!("Took back {word}")).bold().blue());
+        if !session.game.get_guesses().is_empty() {
+            crate::print_guess_history(session.game.get_guesses());
+            println!("--------------");
+            crate::print_alphabet(session.game.get_alphabet(), session.game.get_language());
+        }
+    } else {
+        println!("UNDO {word}");
+    }
+    Ok(None)
+}
+
+fn candidates(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    let candidates = solver::get_candidates(session.answer_list, session.game.get_guesses());
+    let shown: Vec<&str> = candidates
+        .iter()
+        .take(CANDIDATES_SHOWN)
+        .map(|word| word.as_str())
+        .collect();
+    if !session.is_tty {
+        println!("{} {}", candidates.len(), shown.join(" "));
+        return Ok(None);
+    }
+    println!(
+        "{} {}",
+        console::style(format!("{} candidates:", candidates.len())).bold(),
+        shown.join(", ")
+    );
+    if candidates.len() > shown.len() {
+        println!("...and {} more", candidates.len() - shown.len());
+    }
+    Ok(None)
+}
+
+fn stats(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    let filtered = session.stats.filtered(&session.args.get_filter());
+    let stats = filtered.as_ref().unwrap_or(session.stats);
+    stats.print(session.is_tty, session.args.get_stats_top());
+    Ok(None)
+}
+
+fn define(session: &mut Session, words: &[&str]) -> Result<Option<GameStatus>, String> {
+    if words.is_empty() {
+        return Err(format!("Usage: {PREFIX}define WORD..."));
+    }
+    let language = session.game.get_language();
+    // Several words can be looked up at once, as spelled in the word list
+    for word in words {
+        let word = language.to_upper(word);
+        let word = language
+            .find_word(&word, session.word_list)
+            .map_or(word.as_str(), |word| word.as_str());
+        if !crate::print_definition(word, session.metadata, session.dictionary, session.is_tty) {
+            crate::print_undefined(session.is_tty, word);
+        }
+    }
+    Ok(None)
+}
+
+fn share(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    if !session.game.is_over() {
+        return Err("The game can be shared once it is over".to_string());
+    }
+    let guesses = session.game.get_guesses();
+    let won = guesses.last().is_some_and(|(_, status)| {
+        status.iter().all(|&letter| letter == LetterStatus::Green)
+    });
+    let rounds = if won {
+        guesses.len().to_string()
+    } else {
+        "X".to_string()
+    };
+    // Games in difficult mode are marked as the original does
+    let difficult = if session.game.is_difficult() { "*" } else { "" };
+    println!("Wordle {rounds}/{MAX_GAME_ROUND}{difficult}");
+    for (_, status) in guesses {
+        let squares: String = status
+            .iter()
+            .map(|letter| match letter {
+                LetterStatus::Green => '🟩',
+                LetterStatus::Yellow => '🟨',
+                _ => '⬛',
+            })
+            .collect();
+        println!("{squares}");
+    }
+    Ok(None)
+}
+
+fn give_up(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    Ok(Some(session.game.give_up()))
+}
+
+fn help(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    for command in COMMANDS {
+        let usage = format!("{PREFIX}{} {}", command.name, command.usage);
+        if session.is_tty {
+            println!(
+                "{} {}",
+                console::style(format!("{usage:<16}")).bold().blue(),
+                command.help
+            );
+        } else {
+            println!("{}", usage.trim_end());
+        }
+    }
+    Ok(None)
+}
+
+fn quit(session: &mut Session, _: &[&str]) -> Result<Option<GameStatus>, String> {
+    crate::exit_game(session.is_tty);
+}
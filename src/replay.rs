@@ -0,0 +1,409 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::args::Strategy;
+use crate::game::{get_names, Game, LetterStatus};
+use crate::language::{self, Language};
+use crate::race::{self, Standing};
+use crate::selection;
+use crate::words::Metadata;
+
+/// What a race is played with, enough to verify it with the same word lists
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Header {
+    pub language: String,
+    /// Name of the answer list, as recorded along with games
+    pub word_list: String,
+    /// Hash of the word lists, as told by `hash_word_lists`
+    pub word_list_hash: String,
+    /// How the answer was chosen, unless it was given
+    pub seed: Option<u64>,
+    pub strategy: Option<Strategy>,
+    pub target_difficulty: Option<f64>,
+    /// The day of the answer, counting from 1
+    pub day: Option<u32>,
+    pub difficult: bool,
+    pub ignore_accents: bool,
+    pub players: Vec<String>,
+}
+
+/// Something happening in a race
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(tag = "step", rename_all = "snake_case")]
+pub enum Step {
+    Guess {
+        player: String,
+        word: String,
+        status: Vec<String>,
+    },
+    GameOver {
+        player: String,
+        won: bool,
+        rounds: usize,
+    },
+    Left {
+        player: String,
+    },
+    RaceOver {
+        answer: String,
+        winner: Option<String>,
+    },
+}
+
+/// A step, and when it happened from the start of the race
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Entry {
+    pub time_ms: u64,
+    #[serde(flatten)]
+    pub step: Step,
+}
+
+/// A race recorded step by step
+#[derive(Serialize, Deserialize)]
+pub struct Replay {
+    pub race: Header,
+    pub answer: String,
+    pub steps: Vec<Entry>,
+}
+
+/// Hash the word lists as CRC-32 in hex, of the answer list and then the acceptable list,
+/// each sorted with a word per line, so that it can be told by other tools as well
+pub fn hash_word_lists(word_list: &[String], answer_list: &[String]) -> String {
+    let mut hasher = crc32fast::Hasher::new();
+    for list in [answer_list, word_list] {
+        let mut list = list.to_vec();
+        list.sort();
+        for word in list {
+            hasher.update(word.as_bytes());
+            hasher.update(b"\n");
+        }
+    }
+    format!("{:08x}", hasher.finalize())
+}
+
+pub fn load(path: &Path) -> Result<Replay, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open replay: {e}"))?;
+    serde_json::from_reader(BufReader::new(file)).map_err(|e| format!("Failed to load replay: {e}"))
+}
+
+pub fn save(replay: &Replay, path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("Failed to save replay: {e}"))?;
+    serde_json::to_writer_pretty(BufWriter::new(file), replay)
+        .map_err(|e| format!("Failed to save replay: {e}"))
+}
+
+/// Check a replay with the word lists given, returning the problems found
+pub fn verify(
+    replay: &Replay,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    metadata: &Metadata,
+) -> Vec<String> {
+    let race = &replay.race;
+    let mut problems = vec![];
+    if race.language != language.name {
+        problems.push(format!("Played in another language: {}", race.language));
+    }
+    if race.word_list_hash != hash_word_lists(word_list, answer_list) {
+        problems.push(format!("Played with other word lists: {}", race.word_list));
+    }
+    // The answer is chosen again, the same way as in random mode
+    if let (Some(seed), Some(day)) = (race.seed, race.day) {
+        let order = selection::order(
+            answer_list.to_vec(),
+            race.strategy.unwrap_or(Strategy::Uniform),
+            seed,
+            metadata,
+            race.target_difficulty.unwrap_or_default(),
+        );
+        if day.checked_sub(1).and_then(|day| order.get(day as usize)) != Some(&replay.answer) {
+            problems.push("The answer is not chosen by the seed and the day".to_string());
+        }
+    }
+
+    // Guesses are made again, in a game of each player
+    let new_game = || {
+        Game::new(
+            &replay.answer,
+            race.difficult,
+            race.ignore_accents,
+            answer_list,
+            language,
+        )
+    };
+    if new_game().is_err() {
+        problems.push("The answer is not in the answer list".to_string());
+        return problems;
+    }
+    let mut players: HashMap<&str, Player> = race
+        .players
+        .iter()
+        .map(|player| (player.as_str(), Player::new(new_game().unwrap())))
+        .collect();
+    let mut over = false;
+    for entry in &replay.steps {
+        if over {
+            problems.push("There are steps after the race is over".to_string());
+            break;
+        }
+        if let Err(problem) = check_step(entry, &mut players, word_list) {
+            problems.push(problem);
+            continue;
+        }
+        // The result of the race is worked out from the games replayed
+        if let Step::RaceOver { answer, winner } = &entry.step {
+            over = true;
+            if *answer != replay.answer {
+                problems.push(format!("The answer told at the end is not {}", replay.answer));
+            }
+            let mut standings = vec![];
+            for player in &race.players {
+                let played = &players[player.as_str()];
+                match played.finish {
+                    Some((won, time_ms)) => standings.push(Standing {
+                        player: player.to_string(),
+                        won,
+                        rounds: played.game.get_round(),
+                        time_ms,
+                    }),
+                    None if played.broken => (),
+                    None => problems.push(format!("The race is over before {player} is done")),
+                }
+            }
+            // Games not replayed can't be ranked, and their problems are already told
+            if standings.len() == players.len() && race::rank(&mut standings) != *winner {
+                problems.push("The winner is not as played".to_string());
+            }
+        }
+    }
+    if !over {
+        problems.push("The race is not over".to_string());
+    }
+    problems
+}
+
+/// The game of a player in a replay, as it is played again
+struct Player {
+    game: Game,
+    /// Whether the player won, and when the game was over
+    finish: Option<(bool, u64)>,
+    /// Whether a guess was not as played, after which the game is not checked any more
+    broken: bool,
+}
+
+impl Player {
+    fn new(game: Game) -> Self {
+        Self {
+            game,
+            finish: None,
+            broken: false,
+        }
+    }
+}
+
+/// Check a step against the games played again, returning the problem found if any
+fn check_step(
+    entry: &Entry,
+    players: &mut HashMap<&str, Player>,
+    word_list: &[String],
+) -> Result<(), String> {
+    let player = match &entry.step {
+        Step::Guess { player, .. } | Step::GameOver { player, .. } | Step::Left { player } => {
+            player
+        }
+        Step::RaceOver { .. } => return Ok(()),
+    };
+    let played = players
+        .get_mut(player.as_str())
+        .ok_or_else(|| format!("{player} is not in the race"))?;
+    if played.broken {
+        return Ok(());
+    }
+    match &entry.step {
+        Step::Guess { word, status, .. } => {
+            if played.finish.is_some() || played.game.is_over() {
+                return Err(format!("The guess {word} of {player} is made after the game is over"));
+            }
+            let same = played.game.guess(word, word_list).is_ok()
+                && get_names::<String>(&played.game.get_guesses().last().unwrap().1) == *status;
+            if !same {
+                played.broken = true;
+                return Err(format!("The guess {word} of {player} is not as played"));
+            }
+        }
+        Step::GameOver { won, rounds, .. } => {
+            if played.finish.is_some() {
+                return Err(format!("The game of {player} is over twice"));
+            }
+            let game = &played.game;
+            let won_played = game.get_guesses().last().is_some_and(|(_, status)| {
+                status.iter().all(|&letter| letter == LetterStatus::Green)
+            });
+            if !game.is_over() || *won != won_played || *rounds != game.get_round() {
+                played.broken = true;
+                return Err(format!("The result of {player} is not as played"));
+            }
+            played.finish = Some((*won, entry.time_ms));
+        }
+        Step::Left { .. } => {
+            if played.finish.is_some() {
+                return Err(format!("{player} left after the game is over"));
+            }
+            played.finish = Some((false, entry.time_ms));
+        }
+        Step::RaceOver { .. } => (),
+    }
+    Ok(())
+}
+
+/// Print who played, and how the answer was chosen, in tty mode
+pub fn print_header(race: &Header) {
+    println!(
+        "{}",
+        console::style(format!("Race of {}", race.players.join(", "))).bold()
+    );
+    let chosen = match (race.seed, race.day) {
+        (Some(seed), Some(day)) => format!("seed {seed}, day {day}"),
+        _ => "an answer given".to_string(),
+    };
+    let mode = if race.difficult {
+        ", difficult mode"
+    } else {
+        ""
+    };
+    println!(
+        "{}",
+        console::style(format!("Played with {} by {chosen}{mode}", race.word_list)).dim()
+    );
+}
+
+/// Print a step of a race
+pub fn print_step(step: &Step, is_tty: bool) {
+    match step {
+        Step::Guess {
+            player,
+            word,
+            status,
+        } => {
+            let status = race::get_status(status);
+            if is_tty {
+                print!("{}: ", console::style(player).bold());
+                for (j, c) in language::letters(word).enumerate() {
+                    print!("{}", status[j].colored(c));
+                }
+                println!();
+            } else {
+                print!("{player} {word} ");
+                crate::print_status(&status);
+                println!();
+            }
+        }
+        Step::GameOver {
+            player,
+            won,
+            rounds,
+        } => match (is_tty, won) {
+            (true, true) => println!(
+                "{}",
+                console::style(format!("{player} won in {rounds} guesses!"))
+                    .bold()
+                    .magenta()
+            ),
+            (true, false) => println!(
+                "{}",
+                console::style(format!("{player} failed")).bold().red()
+            ),
+            (false, true) => println!("{player} CORRECT {rounds}"),
+            (false, false) => println!("{player} FAILED"),
+        },
+        Step::Left { player } => {
+            if is_tty {
+                println!(
+                    "{}",
+                    console::style(format!("{player} left the race")).yellow()
+                );
+            } else {
+                println!("{player} LEFT");
+            }
+        }
+        Step::RaceOver { answer, winner } => {
+            if !is_tty {
+                println!("ANSWER {answer}");
+                match winner {
+                    Some(winner) => println!("WINNER {winner}"),
+                    None => println!("NO WINNER"),
+                }
+                return;
+            }
+            println!(
+                "{}",
+                console::style(format!("The answer is: {answer}")).bold()
+            );
+            match winner {
+                Some(winner) => println!(
+                    "{}",
+                    console::style(format!("{winner} wins!")).bold().magenta()
+                ),
+                None => println!("{}", console::style("Nobody wins.").bold()),
+            }
+        }
+    }
+}
+
+/// Play back a replay, waiting for Enter before each step in tty mode, and tell whether
+/// it is verified with the word lists given
+pub fn play(
+    path: &Path,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    metadata: &Metadata,
+    is_tty: bool,
+) -> Result<bool, String> {
+    let replay = load(path)?;
+    let problems = verify(&replay, language, word_list, answer_list, metadata);
+
+    if is_tty {
+        print_header(&replay.race);
+    }
+    for entry in &replay.steps {
+        if is_tty {
+            print!(
+                "{}",
+                console::style("Press Enter for the next step...").dim()
+            );
+            crate::flush();
+            if crate::read_line().is_none() {
+                println!();
+                break;
+            }
+        }
+        print_step(&entry.step, is_tty);
+    }
+
+    for problem in &problems {
+        if is_tty {
+            println!("{}", console::style(problem).yellow());
+        } else {
+            println!("UNVERIFIED {problem}");
+        }
+    }
+    if problems.is_empty() {
+        if is_tty {
+            println!(
+                "{}",
+                console::style("Verified with the word lists given")
+                    .bold()
+                    .green()
+            );
+        } else {
+            println!("VERIFIED");
+        }
+    }
+    Ok(problems.is_empty())
+}
@@ -0,0 +1,199 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::args::{Args, Strategy};
+use crate::language::Language;
+use crate::replay;
+use crate::solver;
+use crate::stats::Stats;
+use crate::words::Metadata;
+
+/// Answer lists at least this long are solved once and cached, as solving them takes a while
+const CACHED_ANSWERS: usize = 500;
+
+/// Version of the guesses cached, to be bumped once the solver guesses otherwise
+const CACHE_VERSION: u32 = 1;
+
+/// Order answers for random mode, which are then walked day by day.
+/// The order only depends on the answers, their metadata and the seed
+pub fn order(
+    answers: Vec<String>,
+    strategy: Strategy,
+    seed: u64,
+    metadata: &Metadata,
+    target: f64,
+) -> Vec<String> {
+    let difficulties = match strategy {
+        Strategy::Difficulty => get_difficulties(&answers, metadata),
+        _ => HashMap::new(),
+    };
+    order_by(answers, strategy, seed, metadata, target, &difficulties)
+}
+
+/// Order answers as `order` does, with their difficulties got before by `get_difficulties`,
+/// so that orders by several seeds don't solve the answers again.
+/// The difficulties are only needed by the difficulty strategy
+pub fn order_by(
+    mut answers: Vec<String>,
+    strategy: Strategy,
+    seed: u64,
+    metadata: &Metadata,
+    target: f64,
+    difficulties: &HashMap<String, f64>,
+) -> Vec<String> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    match strategy {
+        Strategy::Uniform => answers.shuffle(&mut rng),
+        Strategy::Frequency => {
+            let weights = get_weights(&answers, metadata);
+            // Weighted random sampling without replacement (Efraimidis and Spirakis),
+            // with keys ln(u) / w, so that small weights don't underflow
+            let mut keyed: Vec<(f64, String)> = answers
+                .into_iter()
+                .zip(weights)
+                .map(|(word, weight)| (rng.gen::<f64>().ln() / weight, word))
+                .collect();
+            keyed.sort_by(|(a, _), (b, _)| b.total_cmp(a));
+            answers = keyed.into_iter().map(|(_, word)| word).collect();
+        }
+        Strategy::Difficulty => {
+            // Answers as hard are met in random order
+            answers.shuffle(&mut rng);
+            let mut keyed: Vec<(f64, String)> = answers
+                .into_iter()
+                .map(|word| ((difficulties[&word] - target).abs(), word))
+                .collect();
+            keyed.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+            answers = keyed.into_iter().map(|(_, word)| word).collect();
+        }
+    }
+    answers
+}
+
+/// Get the frequencies of answers as weights.
+/// Answers without a positive frequency are weighted as the least frequent known one
+fn get_weights(answers: &[String], metadata: &Metadata) -> Vec<f64> {
+    let frequencies: Vec<Option<f64>> = answers
+        .iter()
+        .map(|word| {
+            metadata
+                .get(word)
+                .and_then(|entry| entry.frequency)
+                .filter(|&frequency| frequency > 0.0)
+        })
+        .collect();
+    let least = frequencies
+        .iter()
+        .flatten()
+        .copied()
+        .reduce(f64::min)
+        .unwrap_or(1.0);
+    frequencies
+        .into_iter()
+        .map(|frequency| frequency.unwrap_or(least))
+        .collect()
+}
+
+/// Get the difficulties of answers, as average guesses to find them.
+/// Those not given by the metadata are the guesses the solver takes
+pub fn get_difficulties(answers: &[String], metadata: &Metadata) -> HashMap<String, f64> {
+    let given: Vec<Option<f64>> = answers
+        .iter()
+        .map(|word| metadata.get(word).and_then(|entry| entry.difficulty))
+        .collect();
+    // Solving is expensive, so only do it when needed
+    let solved = if given.iter().any(Option::is_none) {
+        count_guesses(answers)
+    } else {
+        Default::default()
+    };
+    answers
+        .iter()
+        .zip(given)
+        .map(|(word, difficulty)| {
+            (word.clone(), difficulty.unwrap_or_else(|| solved[word] as f64))
+        })
+        .collect()
+}
+
+/// Get the file caching the guesses the solver takes for an answer list, named by its hash
+fn get_cache_file(answers: &[String]) -> Option<PathBuf> {
+    let hash = replay::hash_word_lists(&[], answers);
+    let name = format!("guesses-v{CACHE_VERSION}-{hash}.json");
+    Some(dirs_next::cache_dir()?.join("wordle").join(name))
+}
+
+/// Count how many guesses the solver takes to find each answer,
+/// with those of long answer lists kept in the cache directory of the user
+fn count_guesses(answers: &[String]) -> HashMap<String, usize> {
+    let path = Some(answers)
+        .filter(|answers| answers.len() >= CACHED_ANSWERS)
+        .and_then(get_cache_file);
+    let cached = path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str::<HashMap<String, usize>>(&contents).ok())
+        .filter(|guesses| answers.iter().all(|word| guesses.contains_key(word)));
+    if let Some(guesses) = cached {
+        return guesses;
+    }
+
+    let guesses = solver::count_guesses(answers);
+    // The cache only saves time, so failing to write it is fine
+    if let Some(path) = path {
+        let temp = path.with_extension("tmp");
+        let _ = fs::create_dir_all(path.parent().unwrap())
+            .and_then(|()| fs::write(&temp, serde_json::to_string(&guesses).unwrap()))
+            .and_then(|()| fs::rename(&temp, &path));
+    }
+    guesses
+}
+
+/// Get the index of the answer to play on a day, skipping forward past answers already played.
+/// The day itself is kept if every answer has been played
+pub fn skip_played(answers: &[String], day: usize, played: &HashSet<&String>) -> usize {
+    (0..answers.len())
+        .map(|offset| (day + offset) % answers.len())
+        .find(|&index| !played.contains(&answers[index]))
+        .unwrap_or(day)
+}
+
+/// Find an answer given by the player in the answer list, as it may be given without its accents
+pub fn find_answer(language: &Language, word: &str, answers: &[String]) -> String {
+    let answer = language.to_upper(word);
+    match language.find_word(&answer, answers) {
+        Some(answer) => answer.clone(),
+        None => answer,
+    }
+}
+
+/// Choose the answer of a new game: the word given, by the request or the arguments,
+/// or the answer of the day in random mode, where answers played before may be skipped.
+/// None if the answer is to be asked for
+pub fn choose_answer(
+    args: &Args,
+    word: Option<&String>,
+    answers: &[String],
+    stats: &Stats,
+    day: &mut u32,
+) -> Option<String> {
+    if let Some(word) = word.or(args.word.as_ref()) {
+        Some(find_answer(args.get_language(), word, answers))
+    } else if args.random {
+        if args.fresh {
+            let played = stats.get_games().iter().map(|game| &game.answer).collect();
+            *day = skip_played(answers, *day as usize, &played) as u32;
+        }
+        Some(answers[*day as usize].clone())
+    } else {
+        None
+    }
+}
+
+/// Get the day after a game is started, going back to the first answer after the last one
+pub fn next_day(day: u32, answers: &[String]) -> u32 {
+    (day + 1) % answers.len() as u32
+}
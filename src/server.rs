@@ -0,0 +1,642 @@
+use std::collections::{BTreeMap, HashMap};
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::args::{self, Args, Strategy};
+use crate::game::{
+    get_alphabet_names, get_names, Error, Game, GameStatus, GuessStatus, MAX_GAME_ROUND,
+};
+use crate::language::Language;
+use crate::packs;
+use crate::replay::{self, Entry, Replay, Step};
+use crate::selection;
+use crate::stats::Stats;
+use crate::words::Metadata;
+
+/// Options of a new game, all of which can be left out
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct NewGame {
+    /// The player whose statistics the game counts in, the default profile if not given
+    player: Option<String>,
+    /// The answer, chosen randomly by the seed and the day if not given
+    word: Option<String>,
+    seed: Option<u64>,
+    /// Which of the answers ordered by the seed to play, counting from 1.
+    /// Days are walked one by one if not given
+    day: Option<u32>,
+    #[serde(default)]
+    difficult: bool,
+    /// Play the answers of a pack, instead of the word lists of the server
+    pack: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NewGuess {
+    word: String,
+}
+
+/// A guess made, as shown in the state of a game
+#[derive(Serialize)]
+struct Guess<'a> {
+    word: &'a str,
+    status: Vec<&'static str>,
+}
+
+/// State of a game, where the answer is only told when the game is over
+#[derive(Serialize)]
+struct State<'a> {
+    id: u64,
+    player: Option<&'a str>,
+    difficult: bool,
+    word_list: &'a str,
+    round: usize,
+    max_rounds: usize,
+    status: &'static str,
+    guesses: Vec<Guess<'a>>,
+    alphabet: BTreeMap<&'a str, &'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    answer: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct Hint {
+    word: String,
+}
+
+/// How often a word is used, in statistics
+#[derive(Serialize)]
+struct WordUsage<'a> {
+    word: &'a str,
+    count: usize,
+}
+
+#[derive(Serialize)]
+struct PlayerStats<'a> {
+    player: Option<&'a str>,
+    wins: i32,
+    fails: i32,
+    average_tries: f64,
+    favorite_words: Vec<WordUsage<'a>>,
+}
+
+/// A request which can't be served, with the HTTP status telling why
+#[derive(Debug)]
+struct Failure {
+    code: u16,
+    reason: &'static str,
+    message: String,
+}
+
+impl Failure {
+    fn new(code: u16, reason: &'static str, message: &str) -> Self {
+        Self {
+            code,
+            reason,
+            message: message.to_string(),
+        }
+    }
+
+    /// A request breaking the rules of the game
+    fn from_game(error: &Error) -> Self {
+        Self {
+            code: 422,
+            reason: error.get_reason(),
+            message: error.what(),
+        }
+    }
+
+    fn not_found() -> Self {
+        Self::new(404, "not_found", "No such resource.")
+    }
+}
+
+/// Status code and JSON body of a response
+type Reply = Result<(u16, String), Failure>;
+
+fn json(code: u16, body: &impl Serialize) -> Reply {
+    Ok((code, serde_json::to_string(body).unwrap()))
+}
+
+/// Parse the JSON body of a request, where an empty body is taken as an empty object
+fn parse<T: for<'de> Deserialize<'de>>(body: &str) -> Result<T, Failure> {
+    let body = if body.trim().is_empty() { "{}" } else { body };
+    serde_json::from_str(body).map_err(|e| Failure::new(400, "bad_request", &e.to_string()))
+}
+
+/// Decode a component of a query string
+fn decode(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut decoded = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                match u8::from_str_radix(component.get(i + 1..i + 3).unwrap_or(""), 16) {
+                    Ok(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    Err(_) => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Name of a player in replays, as a race would name them
+fn get_player_name(player: &Option<String>) -> String {
+    player.clone().unwrap_or_else(|| "Player 1".to_string())
+}
+
+/// How many games are kept at most, the oldest finished ones being dropped first
+const MAX_SESSIONS: usize = 1024;
+
+/// Word lists games are played with
+struct Lists {
+    /// Name of the answer list, as recorded along with games
+    name: String,
+    word_list: Vec<String>,
+    answer_list: Vec<String>,
+    /// Hash of the word lists, recorded in replays
+    hash: String,
+    /// Difficulties of the answers, got once as solving them is slow
+    difficulties: HashMap<String, f64>,
+    /// Answers ordered by each seed used, for random games
+    orders: HashMap<u64, Vec<String>>,
+}
+
+impl Lists {
+    /// Get the word lists ready to be played, solving the answers if they are ordered by difficulty
+    fn new(
+        name: String,
+        word_list: Vec<String>,
+        answer_list: Vec<String>,
+        args: &Args,
+        metadata: &Metadata,
+    ) -> Self {
+        let difficulties = match args.strategy {
+            Some(Strategy::Difficulty) => selection::get_difficulties(&answer_list, metadata),
+            _ => HashMap::new(),
+        };
+        Self {
+            name,
+            hash: replay::hash_word_lists(&word_list, &answer_list),
+            word_list,
+            answer_list,
+            difficulties,
+            orders: HashMap::new(),
+        }
+    }
+}
+
+/// A game going or over, played by a player with some word lists
+struct Session {
+    game: Game,
+    player: Option<String>,
+    /// The pack played, if any
+    pack: Option<String>,
+    status: GameStatus,
+    /// What the game is played with, and its steps so far, for its replay
+    header: replay::Header,
+    start: Instant,
+    steps: Vec<Entry>,
+}
+
+impl Session {
+    /// Record a step of the game, for its replay
+    fn record(&mut self, step: Step) {
+        self.steps.push(Entry {
+            time_ms: self.start.elapsed().as_millis() as u64,
+            step,
+        });
+    }
+}
+
+/// Games hosted by the server, along with the statistics of their players
+struct Host<'a> {
+    args: &'a Args,
+    language: &'static Language,
+    metadata: &'a Metadata,
+    /// Word lists of the server, and those of packs played so far
+    lists: HashMap<Option<String>, Lists>,
+    sessions: HashMap<u64, Session>,
+    next_id: u64,
+    /// The day of the next random game without a day given, counting from 0
+    day: u32,
+    players: HashMap<Option<String>, Stats>,
+}
+
+impl<'a> Host<'a> {
+    /// Get the word lists of a pack, or those of the server
+    fn get_lists(&mut self, pack: &Option<String>) -> Result<&mut Lists, Failure> {
+        if !self.lists.contains_key(pack) {
+            let name = pack.as_ref().unwrap();
+            let found = packs::find(name, self.args.pack_dir.as_deref(), self.language)
+                .map_err(|message| Failure::new(422, "unknown_pack", &message))?;
+            let mut word_list = found.get_acceptable(self.language);
+            // Sort the word list to accelerate search
+            word_list.sort();
+            let answer_list = found.get_answers(self.language);
+            let lists = Lists::new(
+                packs::get_word_list_name(name),
+                word_list,
+                answer_list,
+                self.args,
+                self.metadata,
+            );
+            self.lists.insert(pack.clone(), lists);
+        }
+        Ok(self.lists.get_mut(pack).unwrap())
+    }
+
+    /// Get the statistics of a player, loading them from the state file at first
+    fn get_stats(&mut self, player: &Option<String>) -> Result<&mut Stats, Failure> {
+        if !self.players.contains_key(player) {
+            let stats = Stats::new(&self.args.state, player)
+                .ok_or_else(|| Failure::new(500, "broken_state", "Failed to load stats."))?;
+            self.players.insert(player.clone(), stats);
+        }
+        Ok(self.players.get_mut(player).unwrap())
+    }
+
+    /// Tell the state of a game
+    fn get_state(&self, id: u64) -> Reply {
+        let session = self.sessions.get(&id).ok_or_else(Failure::not_found)?;
+        let game = &session.game;
+        let (status, answer) = match session.status {
+            GameStatus::Going => ("going", None),
+            GameStatus::Won(_) => ("won", Some(game.get_answer())),
+            GameStatus::Failed(_) => ("failed", Some(game.get_answer())),
+        };
+        json(
+            200,
+            &State {
+                id,
+                player: session.player.as_deref(),
+                difficult: game.is_difficult(),
+                word_list: &self.lists[&session.pack].name,
+                round: game.get_round(),
+                max_rounds: MAX_GAME_ROUND,
+                status,
+                guesses: game
+                    .get_guesses()
+                    .iter()
+                    .map(|(word, status): &(String, GuessStatus)| Guess {
+                        word,
+                        status: get_names(status),
+                    })
+                    .collect(),
+                alphabet: get_alphabet_names(self.language, game.get_alphabet()),
+                answer,
+            },
+        )
+    }
+
+    /// Create a game with the options given
+    fn create(&mut self, options: NewGame) -> Reply {
+        let args = self.args;
+        let language = self.language;
+        let metadata = self.metadata;
+        // Statistics are checked before the game is created, so that broken ones are told early
+        self.get_stats(&options.player)?;
+        let next_day = self.day;
+        let lists = self.get_lists(&options.pack)?;
+
+        // The seed and the day of random games, counting from 1, are kept for replays
+        let mut chosen = None;
+        let answer = if let Some(ref word) = options.word {
+            selection::find_answer(language, word, &lists.answer_list)
+        } else {
+            let day = match options.day {
+                Some(day) if day == 0 || day as usize > lists.answer_list.len() => {
+                    return Err(Failure::new(
+                        422,
+                        "bad_day",
+                        "Day should be between 1 and the number of answers!",
+                    ));
+                }
+                Some(day) => day as usize - 1,
+                None => next_day as usize % lists.answer_list.len(),
+            };
+            let seed = options.seed.or(args.seed).unwrap_or(args::DEFAULT_SEED);
+            chosen = Some((seed, day as u32 + 1));
+            let (answer_list, difficulties) = (&lists.answer_list, &lists.difficulties);
+            let order = lists.orders.entry(seed).or_insert_with(|| {
+                selection::order_by(
+                    answer_list.clone(),
+                    args.strategy.unwrap_or(Strategy::Uniform),
+                    seed,
+                    metadata,
+                    args.target_difficulty.unwrap_or_default(),
+                    difficulties,
+                )
+            });
+            order[day].clone()
+        };
+
+        let game = Game::new(
+            &answer,
+            options.difficult,
+            args.ignore_accents,
+            &lists.answer_list,
+            language,
+        )
+        .map_err(|error| Failure::from_game(&error))?;
+        let header = replay::Header {
+            language: language.name.to_string(),
+            word_list: lists.name.clone(),
+            word_list_hash: lists.hash.clone(),
+            seed: chosen.map(|(seed, _)| seed),
+            strategy: chosen.map(|_| args.strategy.unwrap_or(Strategy::Uniform)),
+            target_difficulty: args.target_difficulty,
+            day: chosen.map(|(_, day)| day),
+            difficult: options.difficult,
+            ignore_accents: args.ignore_accents,
+            players: vec![get_player_name(&options.player)],
+        };
+        if options.word.is_none() && options.day.is_none() {
+            // Another day of playing wordle...
+            self.day += 1;
+        }
+
+        self.evict();
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sessions.insert(
+            id,
+            Session {
+                game,
+                player: options.player,
+                pack: options.pack,
+                status: GameStatus::Going,
+                header,
+                start: Instant::now(),
+                steps: vec![],
+            },
+        );
+        self.get_state(id).map(|(_, body)| (201, body))
+    }
+
+    /// Make room for a new game, dropping the oldest finished game,
+    /// or the oldest game if none is over
+    fn evict(&mut self) {
+        while self.sessions.len() >= MAX_SESSIONS {
+            let finished = self
+                .sessions
+                .iter()
+                .filter(|(_, session)| session.status != GameStatus::Going)
+                .map(|(&id, _)| id)
+                .min();
+            let id = finished.or_else(|| self.sessions.keys().min().copied()).unwrap();
+            self.sessions.remove(&id);
+        }
+    }
+
+    /// Make a guess in a game, recording the game if it is over
+    fn guess(&mut self, id: u64, guess: NewGuess) -> Reply {
+        let session = self.sessions.get_mut(&id).ok_or_else(Failure::not_found)?;
+        if session.status != GameStatus::Going {
+            return Err(Failure::new(409, "game_over", "The game is over."));
+        }
+        let lists = &self.lists[&session.pack];
+        let word = self.language.to_upper(&guess.word);
+        let status = session
+            .game
+            .guess(&word, &lists.word_list)
+            .map_err(|error| Failure::from_game(&error))?;
+        session.record(Step::Guess {
+            player: get_player_name(&session.player),
+            word,
+            status: get_names(&session.game.get_guesses().last().unwrap().1),
+        });
+
+        let mut saved = Ok(());
+        if status != GameStatus::Going {
+            let game = &session.game;
+            // Statistics of the player have been loaded when the game was created
+            let stats = self.players.get_mut(&session.player).unwrap();
+            stats.set_mode(game.is_difficult(), &lists.name);
+            saved = stats
+                .finish(game, &status, self.args.state.is_some())
+                .map(|_| ());
+
+            let player = get_player_name(&session.player);
+            let won = matches!(status, GameStatus::Won(_));
+            let rounds = session.game.get_round();
+            let answer = session.game.get_answer().to_string();
+            session.record(Step::GameOver {
+                player: player.clone(),
+                won,
+                rounds,
+            });
+            session.record(Step::RaceOver {
+                answer,
+                winner: won.then_some(player),
+            });
+        }
+        session.status = status;
+        // The game is over all the same, though it is not kept in the state file
+        saved.map_err(|message| Failure::new(500, "broken_state", &message))?;
+        self.get_state(id)
+    }
+
+    /// Tell the replay of a game over
+    fn replay(&self, id: u64) -> Reply {
+        let session = self.sessions.get(&id).ok_or_else(Failure::not_found)?;
+        if session.status == GameStatus::Going {
+            return Err(Failure::new(409, "game_going", "The game is not over yet."));
+        }
+        json(
+            200,
+            &Replay {
+                race: session.header.clone(),
+                answer: session.game.get_answer().to_string(),
+                steps: session.steps.clone(),
+            },
+        )
+    }
+
+    /// Get a hint for a game going
+    fn hint(&mut self, id: u64) -> Reply {
+        let session = self.sessions.get(&id).ok_or_else(Failure::not_found)?;
+        if session.status != GameStatus::Going {
+            return Err(Failure::new(409, "game_over", "The game is over."));
+        }
+        let word = session
+            .game
+            .get_hint(&self.lists[&session.pack].word_list)
+            .ok_or_else(|| Failure::new(409, "no_hint", "No word is left for a hint."))?;
+        let player = session.player.clone();
+        self.get_stats(&player)?.use_hint();
+        json(200, &Hint { word })
+    }
+
+    /// Tell the statistics of a player
+    fn stats(&mut self, player: Option<String>) -> Reply {
+        let top = self.args.get_stats_top();
+        let stats = self.get_stats(&player)?;
+        json(
+            200,
+            &PlayerStats {
+                player: player.as_deref(),
+                wins: stats.get_wins(),
+                fails: stats.get_fails(),
+                average_tries: stats.get_average_tries(),
+                favorite_words: stats
+                    .get_favorite_words(top)
+                    .into_iter()
+                    .map(|(word, &count)| WordUsage { word, count })
+                    .collect(),
+            },
+        )
+    }
+
+    /// Route a request to its handler
+    fn handle(&mut self, method: &Method, url: &str, body: &str) -> Reply {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        let id = |segment: &str| segment.parse::<u64>().map_err(|_| Failure::not_found());
+
+        match (method, segments.as_slice()) {
+            (Method::Post, ["games"]) => self.create(parse(body)?),
+            (Method::Get, ["games", game]) => self.get_state(id(game)?),
+            (Method::Post, ["games", game, "guesses"]) => self.guess(id(game)?, parse(body)?),
+            (Method::Post, ["games", game, "hint"]) => self.hint(id(game)?),
+            (Method::Get, ["games", game, "replay"]) => self.replay(id(game)?),
+            (Method::Get, ["stats"]) => {
+                let player = query
+                    .split('&')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(key, _)| *key == "player")
+                    .map(|(_, value)| decode(value));
+                self.stats(player)
+            }
+            _ => Err(Failure::not_found()),
+        }
+    }
+
+    /// Serve a request, replying in JSON
+    fn serve(&mut self, mut request: Request) {
+        let mut body = String::new();
+        let reply = match request.as_reader().read_to_string(&mut body) {
+            Ok(_) => self.handle(request.method(), request.url(), &body),
+            Err(e) => Err(Failure::new(400, "bad_request", &e.to_string())),
+        };
+        let (code, body) = reply.unwrap_or_else(|failure| {
+            let error = serde_json::json!({
+                "error": { "reason": failure.reason, "message": failure.message }
+            });
+            (failure.code, error.to_string())
+        });
+        let header = Header::from_bytes("Content-Type", "application/json").unwrap();
+        // The client may have gone, which is none of our business
+        let _ = request.respond(
+            Response::from_string(body)
+                .with_status_code(code)
+                .with_header(header),
+        );
+    }
+}
+
+/// Host games over HTTP on localhost, one request at a time, until killed
+pub fn run(
+    args: &Args,
+    language: &'static Language,
+    word_list: Vec<String>,
+    answer_list: Vec<String>,
+    metadata: &Metadata,
+    port: u16,
+) -> Result<(), String> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|e| format!("Failed to listen on port {port}: {e}"))?;
+    // The port is told, since it is chosen by the system if 0
+    println!("Listening on http://{}", server.server_addr());
+
+    // The answers of the server are solved before games are served, if needed
+    let mut lists = HashMap::new();
+    lists.insert(
+        None,
+        Lists::new(args.get_word_list_name(), word_list, answer_list, args, metadata),
+    );
+    let mut host = Host {
+        args,
+        language,
+        metadata,
+        lists,
+        sessions: HashMap::new(),
+        next_id: 1,
+        day: args.day.unwrap_or(args::DEFAULT_DAY) - 1,
+        players: HashMap::new(),
+    };
+    for request in server.incoming_requests() {
+        host.serve(request);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::language;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// Start a game with the answer given, telling its id
+    fn create(host: &mut Host) -> u64 {
+        let options = NewGame {
+            word: Some("crane".to_string()),
+            ..NewGame::default()
+        };
+        assert_eq!(host.create(options).unwrap().0, 201);
+        host.next_id - 1
+    }
+
+    #[test]
+    fn finished_games_evicted_first() {
+        let args = Args::default();
+        let metadata = Metadata::new();
+        let mut lists = HashMap::new();
+        let (word_list, answer_list) = (words(&["CRANE", "SOUND"]), words(&["CRANE"]));
+        lists.insert(
+            None,
+            Lists::new("builtin".to_string(), word_list, answer_list, &args, &metadata),
+        );
+        let mut host = Host {
+            args: &args,
+            language: language::get("en"),
+            metadata: &metadata,
+            lists,
+            sessions: HashMap::new(),
+            next_id: 1,
+            day: 0,
+            players: HashMap::new(),
+        };
+        for _ in 0..MAX_SESSIONS {
+            create(&mut host);
+        }
+        let guess = NewGuess {
+            word: "crane".to_string(),
+        };
+        host.guess(5, guess).unwrap();
+
+        // The finished game is dropped, though older ones are going
+        let id = create(&mut host);
+        assert_eq!(host.sessions.len(), MAX_SESSIONS);
+        assert!(host.sessions.contains_key(&1) && !host.sessions.contains_key(&5));
+        assert_eq!(host.get_state(5).unwrap_err().code, 404);
+
+        // Then the oldest games
+        create(&mut host);
+        assert!(!host.sessions.contains_key(&1) && host.sessions.contains_key(&id));
+    }
+}
@@ -0,0 +1,92 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::game::{self, GuessStatus, LetterStatus, WORD_LENGTH};
+
+/// Group candidates by the status they would give to a guess
+fn partition<'a>(guess: &str, candidates: &[&'a String]) -> BTreeMap<GuessStatus, Vec<&'a String>> {
+    let mut groups: BTreeMap<GuessStatus, Vec<&String>> = BTreeMap::new();
+    for &candidate in candidates {
+        groups
+            .entry(game::evaluate(candidate, guess))
+            .or_default()
+            .push(candidate);
+    }
+    groups
+}
+
+/// Count the groups a guess splits the candidates into, without collecting them
+fn count_groups(guess: &str, candidates: &[&String]) -> usize {
+    // Each status is numbered in base 4, since there are 4 kinds of letter status
+    let mut seen = [false; 1 << (2 * WORD_LENGTH)];
+    let mut groups = 0;
+    for candidate in candidates {
+        let index = game::evaluate(candidate, guess)
+            .iter()
+            .fold(0, |index, &status| index * 4 + status as usize);
+        if !seen[index] {
+            seen[index] = true;
+            groups += 1;
+        }
+    }
+    groups
+}
+
+/// Pick the candidate which splits the candidates into the most groups,
+/// the first one in alphabetical order on ties
+pub fn best_guess<'a>(candidates: &[&'a String]) -> &'a String {
+    let mut best = candidates[0];
+    let mut best_groups = 0;
+    for &guess in candidates {
+        let groups = count_groups(guess, candidates);
+        if groups > best_groups || (groups == best_groups && guess < best) {
+            best = guess;
+            best_groups = groups;
+        }
+    }
+    best
+}
+
+/// Solve every answer in the candidates at once, as the guesses only depend on former statuses
+fn solve(candidates: &[&String], round: usize, guesses: &mut HashMap<String, usize>) {
+    let guess = best_guess(candidates);
+    for (status, group) in partition(guess, candidates) {
+        if status.iter().all(|&s| s == LetterStatus::Green) {
+            guesses.insert(guess.clone(), round);
+        } else {
+            // The guess itself is never in the group, so the candidates always decrease
+            solve(&group, round + 1, guesses);
+        }
+    }
+}
+
+/// Find the answers which would give the feedback of every guess, sorted
+pub fn get_candidates<'a>(
+    answer_list: &'a [String],
+    feedback: &[(String, GuessStatus)],
+) -> Vec<&'a String> {
+    let mut candidates: Vec<&String> = answer_list
+        .iter()
+        .filter(|answer| {
+            feedback
+                .iter()
+                .all(|(guess, status)| game::evaluate(answer, guess) == *status)
+        })
+        .collect();
+    candidates.sort();
+    candidates.dedup();
+    candidates
+}
+
+/// Count how many guesses the solver takes to find each answer.
+/// The solver only guesses possible answers, picking the one which tells most of them apart
+pub fn count_guesses(answer_list: &[String]) -> HashMap<String, usize> {
+    let mut candidates: Vec<&String> = answer_list.iter().collect();
+    candidates.sort();
+    candidates.dedup();
+
+    let mut guesses = HashMap::new();
+    if !candidates.is_empty() {
+        solve(&candidates, 1, &mut guesses);
+    }
+    guesses
+}
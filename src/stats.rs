@@ -1,14 +1,20 @@
 use std::collections::HashMap;
 #[cfg(not(target_arch = "wasm32"))]
-use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
-#[cfg(not(target_arch = "wasm32"))]
-use serde_json::json;
 
+use super::achievements::{self, Achievement};
 #[cfg(not(target_arch = "wasm32"))]
+use super::game::GameStatus;
 use super::game::GuessStatus;
+use super::language::Language;
+#[cfg(not(target_arch = "wasm32"))]
+use super::storage::{self, Storage};
+
+/// How many favorite words to show by default
+pub const DEFAULT_TOP: usize = 5;
 
 /// Counter for counting words usage
 type Counter = HashMap<String, usize>;
@@ -23,19 +29,78 @@ pub struct Stats {
     tries: i32,
     word_usage: Counter,
     games: Vec<Game>,
-    state_path: Option<PathBuf>,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    storage: Option<Box<dyn Storage>>,
+    #[serde(default)]
+    player: Option<String>,
+    #[serde(skip)]
+    difficult: bool,
+    #[serde(skip)]
+    word_list: String,
+    #[serde(default)]
+    hinted: bool,
+    #[serde(default)]
+    achievements: Vec<Achievement>,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
-struct Game {
-    answer: String,
-    guesses: Vec<String>,
+/// A finished game as kept in the history.
+/// Details are None for games recorded before they were tracked
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Game {
+    pub answer: String,
+    pub guesses: Vec<String>,
+    /// When the game was over
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<DateTime<Utc>>,
+    /// Whether the game was in difficult mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficult: Option<bool>,
+    /// Name of the answer list the game was played with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_list: Option<String>,
+    /// Whether a hint was given during the game
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hinted: Option<bool>,
 }
 
-#[derive(Default, Serialize, Deserialize)]
-pub struct State {
-    total_rounds: Option<u32>,
-    games: Option<Vec<Game>>,
+/// Conditions on which games are counted in stats.
+/// A game whose details are missing doesn't match a condition on that detail
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Filter {
+    pub since: Option<NaiveDate>,
+    pub until: Option<NaiveDate>,
+    pub difficult: Option<bool>,
+    pub word_list: Option<String>,
+}
+
+impl Filter {
+    /// Check if nothing is filtered out
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Check if a game satisfies all conditions
+    pub fn matches(&self, game: &Game) -> bool {
+        let date = game.date.map(|date| date.date_naive());
+        if let Some(since) = self.since {
+            if date.is_none_or(|date| date < since) {
+                return false;
+            }
+        }
+        if let Some(until) = self.until {
+            if date.is_none_or(|date| date > until) {
+                return false;
+            }
+        }
+        if self.difficult.is_some() && game.difficult != self.difficult {
+            return false;
+        }
+        if self.word_list.is_some() && game.word_list != self.word_list {
+            return false;
+        }
+        true
+    }
 }
 
 impl Stats {
@@ -48,54 +113,78 @@ impl Stats {
             tries: 0,
             word_usage: Counter::new(),
             games: vec![],
-            state_path: None,
+            storage: None,
+            player: None,
+            difficult: false,
+            word_list: String::new(),
+            hinted: false,
+            achievements: vec![],
         }
     }
 
-    /// Initialize statistics from scratch or from JSON file.
-    /// Return None if 'state.json' is in invalid format
+    /// Initialize statistics from scratch or from a state file,
+    /// using the profile of the given player.
+    /// Return None if the state file is broken
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn new(state_path: &Option<PathBuf>) -> Option<Self> {
-        let use_state = state_path.is_some();
+    pub fn new(state_path: &Option<PathBuf>, player: &Option<String>) -> Option<Self> {
+        let mut stats = Self::default();
+        stats.player = player.clone();
+
         // Use state mode
-        if use_state {
-            // If the state file exists, read it
-            if PathBuf::from(state_path.as_ref().unwrap()).exists() {
-                if let Ok(state) = serde_json::from_str::<State>(
-                    fs::read_to_string(state_path.as_ref().unwrap())
-                        .unwrap()
-                        .as_str(),
-                ) {
-                    let mut stats = Self::default();
-                    stats.state_path = state_path.clone();
-
-                    // Load stats from file
-                    if let Some(games) = state.games {
-                        for game in games {
-                            stats.games.push(game.clone());
-                            if game.guesses.last()? == &game.answer {
-                                stats.wins += 1;
-                                stats.tries += game.guesses.len() as i32;
-                            } else {
-                                stats.fails += 1;
-                            }
-                            for word in game.guesses {
-                                count(&mut stats.word_usage, word);
-                            }
-                        }
-                    }
-                    Some(stats)
-                } else {
-                    None
-                }
+        if let Some(path) = state_path {
+            let storage = storage::open(path).ok()?;
+
+            // Load stats from file
+            stats.load_games(storage.load(player).ok()?);
+            stats.achievements = storage.load_achievements(player).ok()?;
+            stats.storage = Some(storage);
+        }
+        Some(stats)
+    }
+
+    /// Load statistics of every player in the state file, the anonymous player first.
+    /// Return None if the state file is broken
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn load_players(state_path: &Path) -> Option<Vec<(Option<String>, Self)>> {
+        let mut players = vec![];
+        for (name, games) in storage::open(state_path).ok()?.load_players().ok()? {
+            let mut stats = Self::default();
+            stats.player = name.clone();
+            stats.load_games(games);
+            players.push((name, stats));
+        }
+        Some(players)
+    }
+
+    /// Get stats of the games matching a filter.
+    /// Return None if nothing is filtered out, so that the stats themselves can be used
+    pub fn filtered(&self, filter: &Filter) -> Option<Self> {
+        if filter.is_empty() {
+            return None;
+        }
+        let mut stats = Self {
+            player: self.player.clone(),
+            ..Default::default()
+        };
+        let games = self.games.iter().filter(|game| filter.matches(game));
+        stats.load_games(games.cloned().collect());
+        Some(stats)
+    }
+
+    /// Count the games loaded from a state file into stats
+    fn load_games(&mut self, games: Vec<Game>) {
+        for game in games {
+            self.games.push(game.clone());
+            // Games given up may have no guesses at all
+            if game.guesses.last() == Some(&game.answer) {
+                self.wins += 1;
+                self.tries += game.guesses.len() as i32;
             } else {
-                // Create new state file
-                let mut stats = Self::default();
-                stats.state_path = state_path.clone();
-                Some(stats)
+                self.fails += 1;
+            }
+            for word in game.guesses {
+                count(&mut self.word_usage, word);
             }
-        } else {
-            Some(Self::default())
         }
     }
 
@@ -109,6 +198,16 @@ impl Stats {
         self.fails
     }
 
+    /// Getter for games
+    pub fn get_games(&self) -> &[Game] {
+        &self.games
+    }
+
+    /// Getter for achievements unlocked
+    pub fn get_achievements(&self) -> &[Achievement] {
+        &self.achievements
+    }
+
     /// Get average tries of game won
     pub fn get_average_tries(&self) -> f64 {
         if self.wins == 0 {
@@ -118,27 +217,26 @@ impl Stats {
         }
     }
 
-    /// Get favorite five words
-    pub fn get_favorite_words(&self) -> Vec<(&String, &usize)> {
+    /// Get the most frequently used words
+    pub fn get_favorite_words(&self, top: usize) -> Vec<(&String, &usize)> {
         // Sort used words by usage times
         let mut words: Vec<(&String, &usize)> = self.word_usage.iter().collect();
         words.sort_by(|(word1, cnt1), (word2, cnt2)| {
             if cnt1 != cnt2 {
                 return cnt1.cmp(cnt2);
             }
-            return word1.cmp(word2).reverse();
+            word1.cmp(word2).reverse()
         });
-        words.iter().cloned().rev().take(5).collect()
+        words.iter().cloned().rev().take(top).collect()
     }
 
-    /// Save stats to specified path
+    /// Save the last game to the storage
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn save(&mut self) {
-        let state = State {
-            total_rounds: Some((self.wins + self.fails) as u32),
-            games: Some(self.games.clone()),
-        };
-        fs::write(self.state_path.as_ref().unwrap(), json!(state).to_string()).unwrap();
+    pub fn save(&mut self) -> Result<(), String> {
+        let storage = self.storage.as_mut().unwrap();
+        storage
+            .append(&self.player, self.games.last().unwrap())
+            .map_err(|e| format!("Failed to save state: {e}"))
     }
 
     /// Update the stats of a single guess
@@ -148,16 +246,51 @@ impl Stats {
 
     /// Update stats of the guesses
     #[cfg(not(target_arch = "wasm32"))]
-    fn update_guesses(&mut self, guesses: &Vec<(String, GuessStatus)>, answer: &String) {
-        let mut words: Vec<String> = vec![];
+    fn update_guesses(&mut self, guesses: &Vec<(String, GuessStatus)>, answer: &str) {
         for (word, _) in guesses {
             self.update_guess(word);
-            words.push(word.to_string());
         }
+        self.record_game(answer, guesses);
+    }
+
+    /// Set the mode of the following games, which is recorded along with them
+    pub fn set_mode(&mut self, difficult: bool, word_list: &str) {
+        self.difficult = difficult;
+        self.word_list = word_list.to_string();
+    }
+
+    /// Mark the current game as one in which a hint was given
+    pub fn use_hint(&mut self) {
+        self.hinted = true;
+    }
+
+    /// Add a finished game to the history, without updating guess stats
+    pub fn record_game(&mut self, answer: &str, guesses: &[(String, GuessStatus)]) {
         self.games.push(Game {
             answer: answer.to_string(),
-            guesses: words,
-        })
+            guesses: guesses.iter().map(|(word, _)| word.to_string()).collect(),
+            date: Some(Utc::now()),
+            difficult: Some(self.difficult),
+            word_list: Some(self.word_list.clone()),
+            hinted: Some(self.hinted),
+        });
+        self.hinted = false;
+    }
+
+    /// Unlock achievements earned by the last game, played in a language,
+    /// and save them to the storage if any. Return the achievements newly unlocked
+    pub fn unlock_achievements(&mut self, language: &Language) -> Result<Vec<Achievement>, String> {
+        let unlocked = achievements::check(&self.games, &self.achievements, language);
+        self.achievements.extend(&unlocked);
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(storage) = self.storage.as_mut() {
+            if !unlocked.is_empty() {
+                storage
+                    .unlock(&self.player, &unlocked)
+                    .map_err(|e| format!("Failed to save state: {e}"))?;
+            }
+        }
+        Ok(unlocked)
     }
 
     /// Won a game with given round, update stats
@@ -170,12 +303,13 @@ impl Stats {
 
     /// Won a game, update stats
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn win(&mut self, save: bool, guesses: &Vec<(String, GuessStatus)>) {
+    pub fn win(&mut self, save: bool, guesses: &Vec<(String, GuessStatus)>) -> Result<(), String> {
         self.win_with_guesses_updated(guesses.len());
         self.update_guesses(guesses, &guesses.last().unwrap().0);
         if save {
-            self.save();
+            self.save()?;
         }
+        Ok(())
     }
 
     /// Failed a game, update stats
@@ -187,21 +321,50 @@ impl Stats {
 
     /// Failed a game, update stats
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn fail(&mut self, save: bool, guesses: &Vec<(String, GuessStatus)>, answer: &String) {
+    pub fn fail(
+        &mut self,
+        save: bool,
+        guesses: &Vec<(String, GuessStatus)>,
+        answer: &str,
+    ) -> Result<(), String> {
         self.fails += 1;
         self.update_guesses(guesses, answer);
         if save {
-            self.save();
+            self.save()?;
+        }
+        Ok(())
+    }
+
+    /// Record a game over, saving it to the storage if asked to, and unlock achievements
+    /// earned by it. Return the achievements newly unlocked
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn finish(
+        &mut self,
+        game: &crate::game::Game,
+        game_status: &GameStatus,
+        save: bool,
+    ) -> Result<Vec<Achievement>, String> {
+        let guesses = game.get_guesses();
+        match game_status {
+            GameStatus::Won(_) => self.win(save, guesses)?,
+            GameStatus::Failed(answer) => self.fail(save, guesses, answer)?,
+            GameStatus::Going => return Ok(vec![]),
+        }
+        // Achievements are only tracked with a state file to keep them in
+        if save {
+            self.unlock_achievements(game.get_language())
+        } else {
+            Ok(vec![])
         }
     }
 
     /// Print statistics in tty mode
     #[cfg(not(target_arch = "wasm32"))]
-    pub fn print(&self, is_tty: bool) {
+    pub fn print(&self, is_tty: bool, top: usize) {
         let average_tries = self.get_average_tries();
 
         // Sort used words by usage times
-        let words = self.get_favorite_words();
+        let words = self.get_favorite_words(top);
 
         if is_tty {
             println!("{}", console::style("Statistics:").bold().yellow());
@@ -237,7 +400,52 @@ impl Stats {
                 first = false;
                 print!("{word} {count}");
             }
-            println!("");
+            println!();
+        }
+    }
+}
+
+/// Print statistics of several players side by side
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print_comparison(players: &[(Option<String>, Stats)], is_tty: bool) {
+    const ANONYMOUS: &str = "(anonymous)";
+
+    if is_tty {
+        println!("{}", console::style("Players:").bold().yellow());
+        let width = players
+            .iter()
+            .map(|(name, _)| name.as_deref().unwrap_or(ANONYMOUS).len())
+            .max()
+            .unwrap_or(0)
+            .max("Player".len());
+        println!(
+            "{}",
+            console::style(format!(
+                "    {:width$}  {:>5}  {:>5}  {:>12}",
+                "Player", "Wins", "Fails", "Avg. tries"
+            ))
+            .bold()
+        );
+        for (name, stats) in players {
+            println!(
+                "    {}  {:>5}  {:>5}  {:>12.2}",
+                console::style(format!("{:width$}", name.as_deref().unwrap_or(ANONYMOUS)))
+                    .bold()
+                    .magenta(),
+                stats.wins,
+                stats.fails,
+                stats.get_average_tries(),
+            );
+        }
+    } else {
+        for (name, stats) in players {
+            println!(
+                "{} {} {} {:.2}",
+                name.as_deref().unwrap_or(ANONYMOUS),
+                stats.wins,
+                stats.fails,
+                stats.get_average_tries()
+            );
         }
     }
 }
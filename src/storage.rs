@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use crate::achievements::Achievement;
+use crate::stats::Game;
+
+mod json;
+mod sqlite;
+
+pub use json::JsonStorage;
+pub use sqlite::SqliteStorage;
+
+/// Games of a player, None for the anonymous player
+pub type PlayerGames = (Option<String>, Vec<Game>);
+
+/// A place where game history is kept
+pub trait Storage {
+    /// Load games of a player, None for the anonymous player
+    fn load(&self, player: &Option<String>) -> Result<Vec<Game>, String>;
+
+    /// Load games of every player, the anonymous player first
+    fn load_players(&self) -> Result<Vec<PlayerGames>, String>;
+
+    /// Append a finished game to the history of a player
+    fn append(&mut self, player: &Option<String>, game: &Game) -> Result<(), String>;
+
+    /// Append games of several players at once
+    fn append_players(&mut self, players: &[PlayerGames]) -> Result<(), String> {
+        for (player, games) in players {
+            for game in games {
+                self.append(player, game)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Load achievements unlocked by a player, None for the anonymous player
+    fn load_achievements(&self, player: &Option<String>) -> Result<Vec<Achievement>, String>;
+
+    /// Record achievements unlocked by a player, ignoring those already recorded
+    fn unlock(&mut self, player: &Option<String>, achievements: &[Achievement])
+        -> Result<(), String>;
+}
+
+/// Check if a path should be opened as an SQLite database, judging by its extension
+pub fn is_sqlite(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|ext| ext.to_str()),
+        Some("db" | "sqlite" | "sqlite3")
+    )
+}
+
+/// Open the storage at the given path, choosing the backend by its extension
+pub fn open(path: &Path) -> Result<Box<dyn Storage>, String> {
+    if is_sqlite(path) {
+        Ok(Box::new(SqliteStorage::open(path)?))
+    } else {
+        Ok(Box::new(JsonStorage::open(path)?))
+    }
+}
+
+/// Copy games and achievements of every player from one storage into another,
+/// which must have no games yet, so that games are never imported twice.
+/// Return the number of games imported
+pub fn import(from: &dyn Storage, to: &mut dyn Storage) -> Result<usize, String> {
+    if to.load_players()?.iter().any(|(_, games)| !games.is_empty()) {
+        return Err("the state file to import into already has games".to_string());
+    }
+    let players = from.load_players()?;
+    to.append_players(&players)?;
+    for (player, _) in &players {
+        to.unlock(player, &from.load_achievements(player)?)?;
+    }
+    Ok(players.iter().map(|(_, games)| games.len()).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn import_into_new_storage_only() {
+        let path = std::env::temp_dir().join(format!("wordle-{}-import.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut from = JsonStorage::open(&path).unwrap();
+        let alice = Some("alice".to_string());
+        let game = Game {
+            answer: "CRANE".to_string(),
+            guesses: vec!["SLATE".to_string(), "CRANE".to_string()],
+            date: None,
+            difficult: None,
+            word_list: None,
+            hinted: None,
+        };
+        from.append_players(&[(None, vec![game.clone()]), (alice.clone(), vec![game.clone(); 2])])
+            .unwrap();
+        from.unlock(&alice, &[Achievement::WinInTwo]).unwrap();
+
+        let mut to = SqliteStorage::open(Path::new(":memory:")).unwrap();
+        assert_eq!(import(&from, &mut to), Ok(3));
+        assert_eq!(to.load_players().unwrap(), from.load_players().unwrap());
+        assert_eq!(to.load_achievements(&alice).unwrap(), [Achievement::WinInTwo]);
+
+        // Games are never imported twice
+        assert!(import(&from, &mut to).is_err());
+        assert_eq!(to.load(&alice).unwrap().len(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
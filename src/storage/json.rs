@@ -0,0 +1,132 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use super::{PlayerGames, Storage};
+use crate::achievements::Achievement;
+use crate::stats::Game;
+
+/// Saved games of a single player
+#[derive(Default, Clone, Serialize, Deserialize)]
+struct Profile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_rounds: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    games: Option<Vec<Game>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    achievements: Vec<Achievement>,
+}
+
+/// Content of the state file.
+/// Games of the anonymous player stay at top level to keep old state files valid,
+/// while named players have their own profiles
+#[derive(Default, Serialize, Deserialize)]
+struct State {
+    #[serde(flatten)]
+    default: Profile,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    players: BTreeMap<String, Profile>,
+}
+
+impl State {
+    /// Get the profile of a player, None for the anonymous player
+    fn profile_mut(&mut self, player: &Option<String>) -> &mut Profile {
+        match player {
+            Some(name) => self.players.entry(name.clone()).or_default(),
+            None => &mut self.default,
+        }
+    }
+}
+
+/// The whole history kept in a single JSON file, rewritten on every save
+pub struct JsonStorage {
+    path: PathBuf,
+}
+
+impl JsonStorage {
+    /// Open a JSON state file, which will be created on first save if it doesn't exist
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let storage = Self {
+            path: path.to_path_buf(),
+        };
+        // Make sure the file is valid before playing
+        storage.read()?;
+        Ok(storage)
+    }
+
+    /// Read the state file. Return an empty state if the file doesn't exist
+    fn read(&self) -> Result<State, String> {
+        if !self.path.exists() {
+            return Ok(State::default());
+        }
+        let contents = fs::read_to_string(&self.path).map_err(|e| e.to_string())?;
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    }
+
+    /// Write the state file
+    fn write(&self, state: &State) -> Result<(), String> {
+        fs::write(&self.path, json!(state).to_string()).map_err(|e| e.to_string())
+    }
+}
+
+impl Storage for JsonStorage {
+    fn load(&self, player: &Option<String>) -> Result<Vec<Game>, String> {
+        let mut state = self.read()?;
+        Ok(state.profile_mut(player).games.take().unwrap_or_default())
+    }
+
+    fn load_players(&self) -> Result<Vec<PlayerGames>, String> {
+        let state = self.read()?;
+        let mut players = vec![];
+        // Skip the anonymous player if nobody played without a name
+        if let Some(games) = state.default.games {
+            players.push((None, games));
+        }
+        for (name, profile) in state.players {
+            players.push((Some(name), profile.games.unwrap_or_default()));
+        }
+        Ok(players)
+    }
+
+    fn append(&mut self, player: &Option<String>, game: &Game) -> Result<(), String> {
+        self.append_players(&[(player.clone(), vec![game.clone()])])
+    }
+
+    fn append_players(&mut self, players: &[PlayerGames]) -> Result<(), String> {
+        // The file is read again so that profiles of other players are preserved
+        let mut state = self.read()?;
+        for (player, games) in players {
+            let profile = state.profile_mut(player);
+            let saved = profile.games.get_or_insert_with(Vec::new);
+            saved.extend(games.iter().cloned());
+            profile.total_rounds = Some(saved.len() as u32);
+        }
+        self.write(&state)
+    }
+
+    fn load_achievements(&self, player: &Option<String>) -> Result<Vec<Achievement>, String> {
+        let mut state = self.read()?;
+        Ok(std::mem::take(&mut state.profile_mut(player).achievements))
+    }
+
+    fn unlock(
+        &mut self,
+        player: &Option<String>,
+        achievements: &[Achievement],
+    ) -> Result<(), String> {
+        if achievements.is_empty() {
+            return Ok(());
+        }
+        let mut state = self.read()?;
+        let unlocked = &mut state.profile_mut(player).achievements;
+        for achievement in achievements {
+            if !unlocked.contains(achievement) {
+                unlocked.push(*achievement);
+            }
+        }
+        self.write(&state)
+    }
+}
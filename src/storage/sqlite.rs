@@ -0,0 +1,260 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use super::{PlayerGames, Storage};
+use crate::achievements::Achievement;
+use crate::stats::Game;
+
+/// Schema migrations, the database is at version `i + 1` after running the i-th one
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE IF NOT EXISTS games (
+        id      INTEGER PRIMARY KEY,
+        player  TEXT,
+        answer  TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS guesses (
+        game_id INTEGER NOT NULL REFERENCES games(id),
+        round   INTEGER NOT NULL,
+        word    TEXT NOT NULL,
+        PRIMARY KEY (game_id, round)
+    );
+    CREATE INDEX IF NOT EXISTS games_player ON games(player);
+    ",
+    "
+    ALTER TABLE games ADD COLUMN date TEXT;
+    ALTER TABLE games ADD COLUMN difficult INTEGER;
+    ALTER TABLE games ADD COLUMN word_list TEXT;
+    ",
+    "
+    ALTER TABLE games ADD COLUMN hinted INTEGER;
+    CREATE TABLE achievements (
+        player      TEXT,
+        achievement TEXT NOT NULL
+    );
+    ",
+];
+
+/// History kept in an embedded SQLite database, one row per game and per guess,
+/// so that saving a game doesn't rewrite the whole history
+pub struct SqliteStorage {
+    conn: Connection,
+}
+
+impl SqliteStorage {
+    /// Open an SQLite database, creating or upgrading the tables if needed
+    pub fn open(path: &Path) -> Result<Self, String> {
+        let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+        Self::migrate(&mut conn).map_err(|e| e.to_string())?;
+        Ok(Self { conn })
+    }
+
+    /// Run the migrations which haven't been run on the database
+    fn migrate(conn: &mut Connection) -> rusqlite::Result<()> {
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        for (i, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            let tx = conn.transaction()?;
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", i + 1)?;
+            tx.commit()?;
+        }
+        Ok(())
+    }
+
+    /// Insert a game and its guesses, without committing
+    fn insert(conn: &Connection, player: &Option<String>, game: &Game) -> rusqlite::Result<()> {
+        conn.execute(
+            "INSERT INTO games (player, answer, date, difficult, word_list, hinted)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                player,
+                game.answer,
+                game.date,
+                game.difficult,
+                game.word_list,
+                game.hinted
+            ],
+        )?;
+        let game_id = conn.last_insert_rowid();
+        let mut stmt =
+            conn.prepare_cached("INSERT INTO guesses (game_id, round, word) VALUES (?1, ?2, ?3)")?;
+        for (round, word) in game.guesses.iter().enumerate() {
+            stmt.execute(params![game_id, round as i64 + 1, word])?;
+        }
+        Ok(())
+    }
+
+    /// Load games of a player, in the order they were played.
+    /// Games given up before any guess have no guesses, but are loaded all the same
+    fn query(&self, player: &Option<String>) -> rusqlite::Result<Vec<Game>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT games.id, games.answer, guesses.word,
+                    games.date, games.difficult, games.word_list, games.hinted FROM games
+             LEFT JOIN guesses ON guesses.game_id = games.id
+             WHERE games.player IS ?1
+             ORDER BY games.id, guesses.round",
+        )?;
+        let mut rows = stmt.query(params![player])?;
+
+        // Guesses of a game come in consecutive rows
+        let mut games: Vec<Game> = vec![];
+        let mut last_id = None;
+        while let Some(row) = rows.next()? {
+            let id: i64 = row.get(0)?;
+            if last_id != Some(id) {
+                games.push(Game {
+                    answer: row.get(1)?,
+                    guesses: vec![],
+                    date: row.get(3)?,
+                    difficult: row.get(4)?,
+                    word_list: row.get(5)?,
+                    hinted: row.get(6)?,
+                });
+                last_id = Some(id);
+            }
+            if let Some(word) = row.get(2)? {
+                games.last_mut().unwrap().guesses.push(word);
+            }
+        }
+        Ok(games)
+    }
+
+    /// Load achievements of a player, in the order they were unlocked.
+    /// Unknown achievements are skipped
+    fn query_achievements(&self, player: &Option<String>) -> rusqlite::Result<Vec<Achievement>> {
+        let mut stmt = self.conn.prepare_cached(
+            "SELECT achievement FROM achievements WHERE player IS ?1 ORDER BY rowid",
+        )?;
+        let ids = stmt
+            .query_map(params![player], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(ids.iter().filter_map(|id| Achievement::from_id(id)).collect())
+    }
+}
+
+impl Storage for SqliteStorage {
+    fn load(&self, player: &Option<String>) -> Result<Vec<Game>, String> {
+        self.query(player).map_err(|e| e.to_string())
+    }
+
+    fn load_players(&self) -> Result<Vec<PlayerGames>, String> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT DISTINCT player FROM games ORDER BY player")
+            .map_err(|e| e.to_string())?;
+        // NULL sorts first, so the anonymous player comes first
+        let players = stmt
+            .query_map([], |row| row.get::<_, Option<String>>(0))
+            .and_then(|rows| rows.collect::<rusqlite::Result<Vec<_>>>())
+            .map_err(|e| e.to_string())?;
+        players
+            .into_iter()
+            .map(|player| Ok((player.clone(), self.load(&player)?)))
+            .collect()
+    }
+
+    fn append(&mut self, player: &Option<String>, game: &Game) -> Result<(), String> {
+        self.append_players(&[(player.clone(), vec![game.clone()])])
+    }
+
+    fn append_players(&mut self, players: &[PlayerGames]) -> Result<(), String> {
+        // A single transaction for all games, which is much faster than one per game
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        for (player, games) in players {
+            for game in games {
+                Self::insert(&tx, player, game).map_err(|e| e.to_string())?;
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+
+    fn load_achievements(&self, player: &Option<String>) -> Result<Vec<Achievement>, String> {
+        self.query_achievements(player).map_err(|e| e.to_string())
+    }
+
+    fn unlock(
+        &mut self,
+        player: &Option<String>,
+        achievements: &[Achievement],
+    ) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        for achievement in achievements {
+            tx.execute(
+                "INSERT INTO achievements (player, achievement)
+                 SELECT ?1, ?2 WHERE NOT EXISTS (
+                     SELECT 1 FROM achievements WHERE player IS ?1 AND achievement = ?2
+                 )",
+                params![player, achievement.get_id()],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn game(answer: &str, guesses: &[&str]) -> Game {
+        Game {
+            answer: answer.to_string(),
+            guesses: guesses.iter().map(|guess| guess.to_string()).collect(),
+            date: Some(Utc::now()),
+            difficult: Some(true),
+            word_list: Some("pack:animals".to_string()),
+            hinted: Some(false),
+        }
+    }
+
+    fn open() -> SqliteStorage {
+        SqliteStorage::open(Path::new(":memory:")).unwrap()
+    }
+
+    #[test]
+    fn games_of_players() {
+        let mut storage = open();
+        let alice = Some("alice".to_string());
+        let games = [game("CRANE", &["SLATE", "CRANE"]), game("TITAN", &["CRANE"])];
+        storage.append(&alice, &games[0]).unwrap();
+        storage.append(&None, &games[1]).unwrap();
+
+        // Details are kept along with the guesses
+        assert_eq!(storage.load(&alice).unwrap(), [games[0].clone()]);
+        let players = storage.load_players().unwrap();
+        assert_eq!(players.len(), 2);
+        assert_eq!(players[0], (None, vec![games[1].clone()]));
+        assert_eq!(players[1], (alice, vec![games[0].clone()]));
+    }
+
+    #[test]
+    fn games_without_guesses() {
+        let mut storage = open();
+        // A game given up before any guess, between games with guesses
+        let games = [
+            game("CRANE", &["CRANE"]),
+            game("TITAN", &[]),
+            game("SLATE", &["CRANE", "SLATE"]),
+        ];
+        storage.append_players(&[(None, games.to_vec())]).unwrap();
+        assert_eq!(storage.load(&None).unwrap(), games);
+    }
+
+    #[test]
+    fn achievements_unlocked_once() {
+        let mut storage = open();
+        let alice = Some("alice".to_string());
+        storage.unlock(&alice, &[Achievement::WinInTwo]).unwrap();
+        storage
+            .unlock(&alice, &[Achievement::WinInTwo, Achievement::Alphabet])
+            .unwrap();
+        assert_eq!(
+            storage.load_achievements(&alice).unwrap(),
+            [Achievement::WinInTwo, Achievement::Alphabet]
+        );
+        assert!(storage.load_achievements(&None).unwrap().is_empty());
+    }
+}
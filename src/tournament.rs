@@ -0,0 +1,382 @@
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+
+use crate::achievements;
+use crate::args::{self, Args, Mode, Strategy};
+use crate::dict::Dictionary;
+use crate::game::{Game, GameStatus, MAX_GAME_ROUND, WORD_LENGTH};
+use crate::language::Language;
+use crate::repl::{self, Session};
+use crate::selection;
+use crate::stats::Stats;
+use crate::words::Metadata;
+
+/// Guesses counted for a round failed, one more than the most a round can be won with
+pub const FAILED_GUESSES: usize = MAX_GAME_ROUND + 1;
+
+/// A round of a tournament, whose answer is either fixed or chosen by a seed and a day
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Round {
+    pub answer: Option<String>,
+    pub seed: Option<u64>,
+    /// The day of the answer, counting from 1
+    pub day: Option<u32>,
+}
+
+fn get_default_mode() -> Mode {
+    Mode::Normal
+}
+
+fn get_default_length() -> usize {
+    WORD_LENGTH
+}
+
+/// A tournament, where every participant plays the same rounds
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Tournament {
+    pub name: String,
+    pub participants: Vec<String>,
+    #[serde(default = "get_default_mode")]
+    pub mode: Mode,
+    /// Length of the answers, which can only be 5 for now
+    #[serde(default = "get_default_length")]
+    pub length: usize,
+    pub rounds: Vec<Round>,
+}
+
+/// How a participant played a round
+#[derive(Serialize)]
+pub struct RoundResult {
+    pub answer: String,
+    pub won: bool,
+    /// Guesses counted, which is `FAILED_GUESSES` if failed
+    pub guesses: usize,
+    pub time_ms: u64,
+    pub hints: usize,
+}
+
+/// A row of the leaderboard
+#[derive(Serialize)]
+pub struct Standing {
+    pub rank: usize,
+    pub player: String,
+    pub guesses: usize,
+    pub wins: usize,
+    pub time_ms: u64,
+    pub hints: usize,
+    pub rounds: Vec<RoundResult>,
+}
+
+/// Results of a tournament, ranked
+#[derive(Serialize)]
+pub struct Leaderboard {
+    pub name: String,
+    pub standings: Vec<Standing>,
+}
+
+impl Leaderboard {
+    /// Rank participants by fewest guesses in total, then by least time, and then by fewest hints
+    fn new(name: &str, results: Vec<(String, Vec<RoundResult>)>) -> Self {
+        let mut standings: Vec<Standing> = results
+            .into_iter()
+            .map(|(player, rounds)| Standing {
+                rank: 0,
+                player,
+                guesses: rounds.iter().map(|round| round.guesses).sum(),
+                wins: rounds.iter().filter(|round| round.won).count(),
+                time_ms: rounds.iter().map(|round| round.time_ms).sum(),
+                hints: rounds.iter().map(|round| round.hints).sum(),
+                rounds,
+            })
+            .collect();
+        standings.sort_by_key(|standing| (standing.guesses, standing.time_ms, standing.hints));
+        for (i, standing) in standings.iter_mut().enumerate() {
+            standing.rank = i + 1;
+        }
+        Self {
+            name: name.to_string(),
+            standings,
+        }
+    }
+
+    /// Print the leaderboard, as `RANK PLAYER GUESSES` lines in non-tty mode
+    pub fn print(&self, is_tty: bool) {
+        if !is_tty {
+            for standing in &self.standings {
+                println!("{} {} {}", standing.rank, standing.player, standing.guesses);
+            }
+            return;
+        }
+        println!("{}", console::style(&self.name).bold());
+        for standing in &self.standings {
+            println!(
+                "{}. {}: {} guesses, {} won, {:.1}s, {} hints",
+                standing.rank,
+                console::style(&standing.player).bold(),
+                standing.guesses,
+                standing.wins,
+                standing.time_ms as f64 / 1000.0,
+                standing.hints
+            );
+        }
+    }
+
+    /// Render the leaderboard as a Markdown table
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = format!("# {}\n\n", self.name);
+        markdown.push_str("| Rank | Player | Guesses | Wins | Time | Hints |\n");
+        markdown.push_str("| ---- | ------ | ------- | ---- | ---- | ----- |\n");
+        for standing in &self.standings {
+            markdown.push_str(&format!(
+                "| {} | {} | {} | {} | {:.1}s | {} |\n",
+                standing.rank,
+                standing.player,
+                standing.guesses,
+                standing.wins,
+                standing.time_ms as f64 / 1000.0,
+                standing.hints
+            ));
+        }
+        markdown
+    }
+
+    /// Export the leaderboard, as Markdown if the extension is `.md`, or as JSON otherwise
+    pub fn export(&self, path: &Path) -> Result<(), String> {
+        let content = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md" | "markdown") => self.to_markdown(),
+            _ => serde_json::to_string_pretty(self).unwrap(),
+        };
+        fs::write(path, content).map_err(|e| format!("Failed to export leaderboard: {e}"))
+    }
+}
+
+pub fn load(path: &Path) -> Result<Tournament, String> {
+    let file = File::open(path).map_err(|e| format!("Failed to open tournament: {e}"))?;
+    serde_json::from_reader(BufReader::new(file))
+        .map_err(|e| format!("Failed to load tournament: {e}"))
+}
+
+/// Find the answers of the rounds, checking them with the answer list
+fn get_answers(
+    tournament: &Tournament,
+    language: &Language,
+    answer_list: &[String],
+    metadata: &Metadata,
+) -> Result<Vec<String>, String> {
+    if tournament.participants.is_empty() || tournament.rounds.is_empty() {
+        return Err("A tournament needs participants and rounds".to_string());
+    }
+    if tournament.length != WORD_LENGTH {
+        return Err(format!(
+            "Only answers of {WORD_LENGTH} letters can be played"
+        ));
+    }
+    let mut answers = vec![];
+    for (i, round) in tournament.rounds.iter().enumerate() {
+        let answer = match (&round.answer, round.day) {
+            (Some(word), None) if round.seed.is_none() => {
+                // The answer may be given without its accents
+                let answer = language.to_upper(word);
+                match language.find_word(&answer, answer_list) {
+                    Some(answer) => answer.clone(),
+                    None => return Err(format!("Round {}: {word} is not an answer", i + 1)),
+                }
+            }
+            (None, Some(day)) if day >= 1 && day as usize <= answer_list.len() => {
+                let order = selection::order(
+                    answer_list.to_vec(),
+                    Strategy::Uniform,
+                    round.seed.unwrap_or(args::DEFAULT_SEED),
+                    metadata,
+                    0.0,
+                );
+                order[day as usize - 1].clone()
+            }
+            (None, Some(_)) => {
+                return Err(format!(
+                    "Round {}: day should be between 1 and the number of answers",
+                    i + 1
+                ))
+            }
+            _ => {
+                return Err(format!(
+                    "Round {}: either an answer or a day should be given",
+                    i + 1
+                ))
+            }
+        };
+        answers.push(answer);
+    }
+    Ok(answers)
+}
+
+/// Play a round in the CLI, where commands such as `:hint` can be used as in a game alone.
+/// Return None at EOF
+fn play_round(session: &mut Session) -> Option<()> {
+    let language = session.game.get_language();
+    loop {
+        if session.is_tty {
+            print!(
+                "{}",
+                console::style(format!("Guess {}: ", session.game.get_round() + 1)).blue()
+            );
+            crate::flush();
+        }
+        let line = crate::read_line()?;
+
+        // Commands such as :hint, which may end the round by giving up
+        let game_status = if repl::is_command(&line, session.word_list) {
+            match repl::run(session, &line) {
+                Some(game_status) => game_status,
+                None => continue,
+            }
+        } else {
+            match session.game.guess(&language.to_upper(&line), session.word_list) {
+                Ok(game_status) => {
+                    let guesses = session.game.get_guesses();
+                    if session.is_tty {
+                        crate::print_guess_history(guesses);
+                        println!("--------------");
+                        crate::print_alphabet(session.game.get_alphabet(), language);
+                    } else {
+                        crate::print_status(&guesses.last().unwrap().1);
+                        print!(" ");
+                        crate::print_status(session.game.get_alphabet());
+                        println!();
+                    }
+                    game_status
+                }
+                Err(error) => {
+                    crate::print_error(session.is_tty, &error);
+                    continue;
+                }
+            }
+        };
+
+        match game_status {
+            GameStatus::Won(round) => {
+                if session.is_tty {
+                    println!(
+                        "{}",
+                        console::style(format!("You won in {round} guesses!"))
+                            .bold()
+                            .magenta()
+                    );
+                } else {
+                    println!("CORRECT {round}");
+                }
+                return Some(());
+            }
+            GameStatus::Failed(answer) => {
+                if session.is_tty {
+                    println!(
+                        "{}",
+                        console::style(format!("You lose! The answer is: {answer}"))
+                            .bold()
+                            .red()
+                    );
+                } else {
+                    println!("FAILED {answer}");
+                }
+                return Some(());
+            }
+            GameStatus::Going => (),
+        }
+    }
+}
+
+/// Run a tournament in the CLI, where each participant plays every round in order,
+/// with games recorded in their stats. Return the leaderboard
+#[allow(clippy::too_many_arguments)]
+pub fn run(
+    tournament: &Tournament,
+    args: &Args,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    metadata: &Metadata,
+    dictionary: &Dictionary,
+    is_tty: bool,
+) -> Result<Leaderboard, String> {
+    let answers = get_answers(tournament, language, answer_list, metadata)?;
+    let difficult = tournament.mode == Mode::Difficult;
+    let save = args.state.is_some();
+
+    let mut results = vec![];
+    for participant in &tournament.participants {
+        let player = Some(participant.clone());
+        let mut stats =
+            Stats::new(&args.state, &player).ok_or_else(|| "Failed to load stats".to_string())?;
+        stats.set_mode(difficult, &args.get_word_list_name());
+
+        let mut rounds = vec![];
+        for (i, answer) in answers.iter().enumerate() {
+            if is_tty {
+                println!(
+                    "{}",
+                    console::style(format!(
+                        "{participant}, round {} of {}",
+                        i + 1,
+                        answers.len()
+                    ))
+                    .bold()
+                    .green()
+                );
+            } else {
+                println!("{participant} ROUND {}", i + 1);
+            }
+
+            let mut game = Game::new(
+                answer,
+                difficult,
+                args.ignore_accents,
+                answer_list,
+                language,
+            )
+            .unwrap();
+            // Rounds are ranked, so commands which would help unfairly can't be used
+            let mut session = Session {
+                game: &mut game,
+                stats: &mut stats,
+                args,
+                word_list,
+                answer_list,
+                metadata,
+                dictionary,
+                is_tty,
+                ranked: true,
+                hints: 0,
+            };
+            let start = Instant::now();
+            play_round(&mut session)
+                .ok_or_else(|| "The input ended before the tournament is over".to_string())?;
+            let time_ms = start.elapsed().as_millis() as u64;
+            let hints = session.hints;
+
+            let guesses = game.get_guesses();
+            let won = guesses.last().is_some_and(|(word, _)| word == answer);
+            let game_status = if won {
+                GameStatus::Won(guesses.len())
+            } else {
+                GameStatus::Failed(answer.clone())
+            };
+            let unlocked = stats.finish(&game, &game_status, save)?;
+            achievements::print_unlocked(&unlocked, is_tty);
+            rounds.push(RoundResult {
+                answer: answer.clone(),
+                won,
+                guesses: if won { guesses.len() } else { FAILED_GUESSES },
+                time_ms,
+                hints,
+            });
+        }
+        results.push((participant.clone(), rounds));
+    }
+    Ok(Leaderboard::new(&tournament.name, results))
+}
@@ -0,0 +1,532 @@
+use std::io;
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Alignment, Constraint, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::args::Args;
+use crate::game::{Game, GameStatus, LetterStatus, MAX_GAME_ROUND, WORD_LENGTH};
+use crate::language::{self, Language};
+use crate::selection;
+use crate::stats::Stats;
+
+const STATS_PANE_WIDTH: u16 = 32;
+
+/// What the keys typed go into
+#[derive(PartialEq, Eq)]
+enum Phase {
+    /// The answer of the game, which is hidden as typed
+    Answer,
+    Guess,
+    /// The game is over, waiting for another one
+    Over,
+}
+
+/// A message shown in the status line
+struct Message {
+    text: String,
+    color: Color,
+}
+
+/// Full-screen terminal UI, which redraws in place and takes input key by key
+struct Tui<'a> {
+    args: &'a Args,
+    language: &'static Language,
+    word_list: &'a [String],
+    answer_list: &'a [String],
+    stats: Stats,
+    day: u32,
+    game: Option<Game>,
+    phase: Phase,
+    input: String,
+    message: Option<Message>,
+}
+
+/// Get the background color of a letter of some status, as the colors of the CLI
+fn get_color(status: LetterStatus) -> Color {
+    match status {
+        LetterStatus::Unknown => Color::Indexed(102),
+        LetterStatus::Red => Color::Red,
+        LetterStatus::Yellow => Color::Yellow,
+        LetterStatus::Green => Color::Green,
+    }
+}
+
+/// Render a letter as a cell of the board or the keyboard
+fn cell(letter: &str, background: Color) -> Span<'static> {
+    Span::styled(
+        format!(" {letter} "),
+        Style::new().fg(Color::Black).bg(background).bold(),
+    )
+}
+
+/// Put cells in a line, with spaces between them
+fn row(cells: Vec<Span<'static>>) -> Line<'static> {
+    let mut spans = vec![];
+    for (i, cell) in cells.into_iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(cell);
+    }
+    Line::from(spans)
+}
+
+impl<'a> Tui<'a> {
+    /// Start a new game, or wait for the answer if it is neither given nor random
+    fn start(&mut self) {
+        let answer = selection::choose_answer(
+            self.args,
+            None,
+            self.answer_list,
+            &self.stats,
+            &mut self.day,
+        );
+        let game = answer.map(|answer| {
+            let game = self.new_game(&answer);
+            self.day = selection::next_day(self.day, self.answer_list);
+            game
+        });
+
+        self.phase = if game.is_some() { Phase::Guess } else { Phase::Answer };
+        self.game = game;
+        self.input.clear();
+        self.message = match self.phase {
+            Phase::Answer => Some(Message {
+                text: "Please choose an answer for the game.".to_string(),
+                color: Color::Blue,
+            }),
+            _ => None,
+        };
+    }
+
+    /// Create a game with an answer known to be in the answer list
+    fn new_game(&self, answer: &str) -> Game {
+        Game::new(
+            answer,
+            self.args.difficult,
+            self.args.ignore_accents,
+            self.answer_list,
+            self.language,
+        )
+        .unwrap()
+    }
+
+    /// Show an error in the status line
+    fn error(&mut self, text: String) {
+        self.message = Some(Message {
+            text,
+            color: Color::Red,
+        });
+    }
+
+    /// Handle Enter, which submits what is typed. Return false to quit
+    fn submit(&mut self) -> bool {
+        match self.phase {
+            Phase::Answer => {
+                let answer = selection::find_answer(self.language, &self.input, self.answer_list);
+                match Game::new(
+                    &answer,
+                    self.args.difficult,
+                    self.args.ignore_accents,
+                    self.answer_list,
+                    self.language,
+                ) {
+                    Ok(game) => {
+                        self.game = Some(game);
+                        self.phase = Phase::Guess;
+                        self.message = None;
+                        self.day = selection::next_day(self.day, self.answer_list);
+                    }
+                    Err(error) => self.error(error.what()),
+                }
+                self.input.clear();
+            }
+            Phase::Guess => {
+                let game = self.game.as_mut().unwrap();
+                match game.guess(&self.input, self.word_list) {
+                    Ok(game_status) => {
+                        self.input.clear();
+                        self.message = None;
+                        self.finish(game_status);
+                    }
+                    // The guess is kept to be corrected
+                    Err(error) => self.error(error.what()),
+                }
+            }
+            // The same answer can't be played again
+            Phase::Over if self.args.word.is_some() => return false,
+            Phase::Over => self.start(),
+        }
+        true
+    }
+
+    /// Record the game if it is over after a guess
+    fn finish(&mut self, game_status: GameStatus) {
+        let (mut text, mut color) = match &game_status {
+            GameStatus::Going => return,
+            GameStatus::Won(round) => (format!("You won in {round} guesses!"), Color::Magenta),
+            GameStatus::Failed(answer) => {
+                (format!("You lose! The answer is: {answer}"), Color::Red)
+            }
+        };
+        let game = self.game.as_ref().unwrap();
+        let save = self.args.state.is_some();
+        match self.stats.finish(game, &game_status, save) {
+            Ok(unlocked) => {
+                for achievement in unlocked {
+                    text += &format!(" Achievement unlocked: {}!", achievement.get_name());
+                }
+            }
+            Err(message) => {
+                text += &format!(" {message}");
+                color = Color::Red;
+            }
+        }
+        self.phase = Phase::Over;
+        self.message = Some(Message { text, color });
+    }
+
+    /// Handle a key pressed. Return false to quit
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        match code {
+            KeyCode::Esc => return false,
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Enter => return self.submit(),
+            KeyCode::Backspace => {
+                // Remove the last letter, which may be of several chars
+                if let Some(last) = language::letters(&self.input).last() {
+                    self.input.truncate(self.input.len() - last.len());
+                }
+            }
+            // Get hint
+            KeyCode::Tab if self.phase == Phase::Guess => {
+                self.message = Some(match self.game.as_ref().unwrap().get_hint(self.word_list) {
+                    Some(hint) => {
+                        self.stats.use_hint();
+                        Message {
+                            text: format!("Hint: {hint}"),
+                            color: Color::Blue,
+                        }
+                    }
+                    None => Message {
+                        text: "No word is left for a hint".to_string(),
+                        color: Color::Red,
+                    },
+                });
+            }
+            KeyCode::Char(c) if self.phase != Phase::Over => {
+                let letter = self.language.to_upper(&c.to_string());
+                // Avoid entering more than 5 letters
+                if self.language.get_index(&letter).is_some()
+                    && language::count_letters(&self.input) < WORD_LENGTH
+                {
+                    self.input.push_str(&letter);
+                }
+            }
+            _ => (),
+        }
+        true
+    }
+
+    /// Render the board, with guesses made and the one being typed
+    fn board(&self) -> Vec<Line<'static>> {
+        let guesses = self.game.as_ref().map_or(&[][..], |game| game.get_guesses());
+        let mut lines = vec![];
+        for i in 0..MAX_GAME_ROUND {
+            let cells = if let Some((word, status)) = guesses.get(i) {
+                language::letters(word)
+                    .zip(status)
+                    .map(|(letter, &status)| cell(letter, get_color(status)))
+                    .collect()
+            } else if i == guesses.len() && self.phase != Phase::Over {
+                // The answer is hidden as typed
+                let typed: Vec<&str> = match self.phase {
+                    Phase::Answer => language::letters(&self.input).map(|_| "*").collect(),
+                    _ => language::letters(&self.input).collect(),
+                };
+                (0..WORD_LENGTH)
+                    .map(|j| cell(typed.get(j).copied().unwrap_or(" "), Color::White))
+                    .collect()
+            } else {
+                (0..WORD_LENGTH).map(|_| cell(" ", Color::DarkGray)).collect()
+            };
+            lines.push(row(cells));
+            lines.push(Line::default());
+        }
+        lines
+    }
+
+    /// Render the keyboard in the layout of the language, with letters colored by their status
+    fn keyboard(&self) -> Vec<Line<'static>> {
+        let alphabet = self.game.as_ref().map(|game| game.get_alphabet());
+        self.language
+            .keyboard
+            .iter()
+            .map(|keys| {
+                row(language::letters(keys)
+                    .map(|letter| {
+                        // Letters on the keyboard are in the alphabet
+                        let status = alphabet.map_or(LetterStatus::Unknown, |alphabet| {
+                            alphabet[self.language.get_index(letter).unwrap()]
+                        });
+                        cell(letter, get_color(status))
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+
+    /// Render the statistics of the player
+    fn stats(&self) -> Vec<Line<'static>> {
+        let stats = &self.stats;
+        let mut lines = vec![
+            Line::from(vec![
+                "Wins: ".green().bold(),
+                Span::raw(stats.get_wins().to_string()),
+                "  Fails: ".red().bold(),
+                Span::raw(stats.get_fails().to_string()),
+            ]),
+            Line::from(vec![
+                "Average tries: ".bold(),
+                Span::raw(format!("{:.2}", stats.get_average_tries())),
+            ]),
+            Line::default(),
+            Line::from("Most frequently used words:".blue().bold()),
+        ];
+        for (word, count) in stats.get_favorite_words(self.args.get_stats_top()) {
+            lines.push(Line::from(vec![
+                Span::raw("  "),
+                Span::styled(word.clone(), Style::new().magenta().bold()),
+                Span::raw(format!(": used {count} times")),
+            ]));
+        }
+        lines
+    }
+
+    /// Draw the whole screen
+    fn draw(&self, frame: &mut Frame) {
+        let [main, side] = Layout::horizontal([
+            Constraint::Min(0),
+            Constraint::Length(STATS_PANE_WIDTH),
+        ])
+        .areas(frame.area());
+
+        let board = self.board();
+        let keyboard = self.keyboard();
+        let [title, board_area, keyboard_area, status, help] = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Length(board.len() as u16),
+            Constraint::Length(keyboard.len() as u16 + 1),
+            Constraint::Length(2),
+            Constraint::Min(1),
+        ])
+        .areas(main);
+
+        frame.render_widget(
+            Paragraph::new("Wordle".bold()).alignment(Alignment::Center),
+            title,
+        );
+        frame.render_widget(Paragraph::new(board).alignment(Alignment::Center), board_area);
+        frame.render_widget(
+            Paragraph::new(keyboard).alignment(Alignment::Center),
+            keyboard_area,
+        );
+        if let Some(ref message) = self.message {
+            frame.render_widget(
+                Paragraph::new(message.text.as_str())
+                    .style(Style::new().fg(message.color).add_modifier(Modifier::BOLD))
+                    .alignment(Alignment::Center),
+                status,
+            );
+        }
+
+        let keys = match self.phase {
+            Phase::Answer => "Enter: choose  Esc: quit",
+            Phase::Guess => "Enter: guess  Tab: hint  Esc: quit",
+            Phase::Over if self.args.word.is_some() => "Enter/Esc: quit",
+            Phase::Over => "Enter: new game  Esc: quit",
+        };
+        frame.render_widget(
+            Paragraph::new(keys.dim()).alignment(Alignment::Center),
+            Rect { y: help.bottom().saturating_sub(1), height: 1, ..help },
+        );
+
+        let title = match self.args.player {
+            Some(ref name) => format!(" Statistics of {name} "),
+            None => " Statistics ".to_string(),
+        };
+        frame.render_widget(
+            Paragraph::new(self.stats()).block(Block::bordered().title(title.yellow().bold())),
+            side,
+        );
+    }
+
+    /// Draw and handle keys until the player quits
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                // Releases of keys are reported on some platforms
+                if key.kind == KeyEventKind::Press && !self.handle_key(key.code, key.modifiers) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+/// Play games in the full-screen terminal UI, starting from a day
+pub fn run(
+    args: &Args,
+    language: &'static Language,
+    word_list: &[String],
+    answer_list: &[String],
+    stats: Stats,
+    day: u32,
+) -> io::Result<()> {
+    let mut tui = Tui {
+        args,
+        language,
+        word_list,
+        answer_list,
+        stats,
+        day,
+        game: None,
+        phase: Phase::Answer,
+        input: String::new(),
+        message: None,
+    };
+    tui.start();
+
+    // The terminal is restored even if the game panics
+    let mut terminal = ratatui::init();
+    let result = tui.run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    /// A game in the terminal UI, started but not drawn
+    fn tui<'a>(args: &'a Args, word_list: &'a [String], answer_list: &'a [String]) -> Tui<'a> {
+        let mut tui = Tui {
+            args,
+            language: args.get_language(),
+            word_list,
+            answer_list,
+            stats: Stats::default(),
+            day: 0,
+            game: None,
+            phase: Phase::Answer,
+            input: String::new(),
+            message: None,
+        };
+        tui.start();
+        tui
+    }
+
+    /// Type a word key by key, and press Enter
+    fn submit(tui: &mut Tui, word: &str) -> bool {
+        for c in word.chars() {
+            assert!(tui.handle_key(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        tui.handle_key(KeyCode::Enter, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn answer_typed_then_guessed() {
+        let args = Args::default();
+        let word_list = words(&["CRANE", "SLATE", "SOUND"]);
+        let answer_list = words(&["CRANE", "SOUND"]);
+        let mut tui = tui(&args, &word_list, &answer_list);
+        assert!(tui.phase == Phase::Answer);
+
+        // Letters after the fifth are dropped, and Backspace takes one back
+        for c in "soundx".chars() {
+            tui.handle_key(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+        assert_eq!(tui.input, "SOUND");
+        tui.handle_key(KeyCode::Backspace, KeyModifiers::NONE);
+        assert_eq!(tui.input, "SOUN");
+        assert!(submit(&mut tui, "d"));
+        assert!(tui.phase == Phase::Guess);
+        assert_eq!(tui.day, 1);
+
+        // Unknown words are kept to be corrected
+        assert!(submit(&mut tui, "abcde"));
+        assert_eq!(tui.input, "ABCDE");
+        assert_eq!(tui.message.as_ref().unwrap().color, Color::Red);
+        tui.input.clear();
+
+        assert!(submit(&mut tui, "crane"));
+        assert!(tui.phase == Phase::Guess);
+        assert!(submit(&mut tui, "sound"));
+        assert!(tui.phase == Phase::Over);
+        assert_eq!(tui.message.as_ref().unwrap().text, "You won in 2 guesses!");
+        assert_eq!((tui.stats.get_wins(), tui.stats.get_fails()), (1, 0));
+
+        // Letters are not taken after the game is over, and Enter asks for another answer
+        tui.handle_key(KeyCode::Char('a'), KeyModifiers::NONE);
+        assert!(tui.input.is_empty());
+        assert!(tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(tui.phase == Phase::Answer);
+        assert!(!tui.handle_key(KeyCode::Esc, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn answer_given() {
+        let args = Args {
+            word: Some("crane".to_string()),
+            ..Args::default()
+        };
+        let word_list = words(&["CRANE", "SLATE", "SOUND"]);
+        let answer_list = words(&["CRANE", "SOUND"]);
+        let mut tui = tui(&args, &word_list, &answer_list);
+        assert!(tui.phase == Phase::Guess);
+
+        assert!(tui.handle_key(KeyCode::Tab, KeyModifiers::NONE));
+        assert!(tui.message.as_ref().unwrap().text.starts_with("Hint: "));
+        for word in ["slate", "slate", "slate", "slate", "slate"] {
+            assert!(submit(&mut tui, word));
+        }
+        assert!(tui.phase == Phase::Guess);
+        assert!(submit(&mut tui, "sound"));
+        assert!(tui.phase == Phase::Over);
+        assert_eq!(tui.message.as_ref().unwrap().text, "You lose! The answer is: CRANE");
+        assert_eq!((tui.stats.get_wins(), tui.stats.get_fails()), (0, 1));
+
+        // The same answer can't be played again
+        assert!(!tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn random_answers_day_by_day() {
+        let args = Args {
+            random: true,
+            ..Args::default()
+        };
+        let word_list = words(&["CRANE", "SLATE", "SOUND"]);
+        let answer_list = words(&["CRANE", "SOUND"]);
+        let mut tui = tui(&args, &word_list, &answer_list);
+        assert_eq!(tui.game.as_ref().unwrap().get_answer(), "CRANE");
+        assert!(submit(&mut tui, "crane"));
+        assert!(tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(tui.game.as_ref().unwrap().get_answer(), "SOUND");
+        assert!(submit(&mut tui, "sound"));
+
+        // Days go back to the first answer after the last one
+        assert!(tui.handle_key(KeyCode::Enter, KeyModifiers::NONE));
+        assert_eq!(tui.game.as_ref().unwrap().get_answer(), "CRANE");
+        assert!(!tui.handle_key(KeyCode::Char('c'), KeyModifiers::CONTROL));
+    }
+}
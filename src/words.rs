@@ -0,0 +1,576 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::cmp::Reverse;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::WORD_LENGTH;
+use crate::language::{self, Language};
+
+/// A word of a word list, along with its metadata
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Entry {
+    pub word: String,
+    /// How common the word is, the higher the more common
+    pub frequency: Option<f64>,
+    /// How hard the word is to guess
+    pub difficulty: Option<f64>,
+    /// Themes of the word
+    pub tags: Vec<String>,
+    /// Retired words can be guessed, but are no longer chosen as answers
+    pub retired: bool,
+    pub definition: Option<String>,
+}
+
+impl Entry {
+    /// An entry without metadata
+    pub fn new(word: &str) -> Self {
+        Self {
+            word: word.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Check if the entry carries any metadata
+    fn has_metadata(&self) -> bool {
+        self.frequency.is_some()
+            || self.difficulty.is_some()
+            || !self.tags.is_empty()
+            || self.retired
+            || self.definition.is_some()
+    }
+}
+
+/// An entry along with the line it is at, if known
+pub struct Located {
+    pub line: Option<usize>,
+    pub entry: Entry,
+}
+
+/// Metadata of words, in upper case
+pub type Metadata = HashMap<String, Entry>;
+
+/// Formats of word list files
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Words separated by whitespaces
+    Text,
+    /// An array of words, or of objects with a word and its metadata
+    Json,
+    /// A table with a header, with a word and its metadata in each row
+    Csv,
+}
+
+impl Format {
+    /// Detect the format of a word list file by its extension, plain text by default
+    pub fn detect(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            Some(ext) if ext.eq_ignore_ascii_case("csv") => Self::Csv,
+            _ => Self::Text,
+        }
+    }
+}
+
+/// How serious a problem in a word list is
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The word list can't be used
+    Error,
+    /// The word list can be used, but probably not as intended
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem found in a word list, at a line or in the whole list if `line` is None
+#[derive(Debug)]
+pub struct Diagnostic {
+    pub source: String,
+    pub line: Option<usize>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: ", self.source)?,
+            None => write!(f, "{}: ", self.source)?,
+        }
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+/// Check if any of the diagnostics is an error
+pub fn has_errors(diagnostics: &[Diagnostic]) -> bool {
+    diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.severity == Severity::Error)
+}
+
+/// Join the errors among the diagnostics into a message
+pub fn format_errors(diagnostics: &[Diagnostic]) -> String {
+    let errors: Vec<String> = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    format!("Invalid word list:\n{}", errors.join("\n"))
+}
+
+/// Parse the contents of a word list.
+/// Return the line, if known, and the reason if it is malformed
+pub fn parse(format: Format, contents: &str) -> Result<Vec<Located>, (Option<usize>, String)> {
+    match format {
+        Format::Text => Ok(contents
+            .lines()
+            .enumerate()
+            .flat_map(|(i, line)| {
+                line.split_whitespace().map(move |word| Located {
+                    line: Some(i + 1),
+                    entry: Entry::new(word),
+                })
+            })
+            .collect()),
+        Format::Json => {
+            /// Entries of JSON word lists are either plain words or objects with metadata
+            #[derive(Deserialize)]
+            #[serde(untagged)]
+            enum JsonEntry {
+                Word(String),
+                Entry(Entry),
+            }
+            let entries: Vec<JsonEntry> =
+                serde_json::from_str(contents).map_err(|e| (Some(e.line()), e.to_string()))?;
+            Ok(entries
+                .into_iter()
+                .map(|entry| Located {
+                    line: None,
+                    entry: match entry {
+                        JsonEntry::Word(word) => Entry::new(&word),
+                        JsonEntry::Entry(entry) => entry,
+                    },
+                })
+                .collect())
+        }
+        Format::Csv => parse_csv(contents),
+    }
+}
+
+/// Parse a CSV word list, whose header names the columns.
+/// Only the `word` column is required, and tags are separated by semicolons
+fn parse_csv(contents: &str) -> Result<Vec<Located>, (Option<usize>, String)> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(contents.as_bytes());
+    let headers = reader.headers().map_err(|e| (Some(1), e.to_string()))?.clone();
+    let column = |name: &str| headers.iter().position(|header| header == name);
+    let word_column = column("word").ok_or((Some(1), "missing column 'word'".to_string()))?;
+    let frequency_column = column("frequency");
+    let difficulty_column = column("difficulty");
+    let tags_column = column("tags");
+    let retired_column = column("retired");
+    let definition_column = column("definition");
+
+    let mut entries = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|e| {
+            let line = e.position().map(|position| position.line() as usize);
+            (line, e.to_string())
+        })?;
+        let line = record.position().map(|position| position.line() as usize);
+        // Empty fields are treated as missing
+        let field = |column: Option<usize>| {
+            column
+                .and_then(|column| record.get(column))
+                .filter(|field| !field.is_empty())
+        };
+        let number = |name: &str, column| {
+            field(column)
+                .map(|field| {
+                    field
+                        .parse::<f64>()
+                        .map_err(|_| (line, format!("invalid {name} '{field}'")))
+                })
+                .transpose()
+        };
+
+        entries.push(Located {
+            line,
+            entry: Entry {
+                word: field(Some(word_column)).unwrap_or_default().to_string(),
+                frequency: number("frequency", frequency_column)?,
+                difficulty: number("difficulty", difficulty_column)?,
+                tags: field(tags_column)
+                    .map(|tags| tags.split(';').map(|tag| tag.trim().to_string()).collect())
+                    .unwrap_or_default(),
+                retired: match field(retired_column) {
+                    None | Some("false" | "0" | "no") => false,
+                    Some("true" | "1" | "yes") => true,
+                    Some(field) => return Err((line, format!("invalid retired flag '{field}'"))),
+                },
+                definition: field(definition_column).map(|field| field.to_string()),
+            },
+        });
+    }
+    Ok(entries)
+}
+
+/// Check the contents of a word list, whose words should be in the alphabet of a language.
+/// Words of a final list are also checked against the acceptable words if given
+pub fn lint(
+    source: &str,
+    format: Format,
+    contents: &str,
+    acceptable: Option<&HashSet<String>>,
+    language: &Language,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = vec![];
+    let mut report = |line, severity, message| {
+        diagnostics.push(Diagnostic {
+            source: source.to_string(),
+            line,
+            severity,
+            message,
+        })
+    };
+
+    let entries = match parse(format, contents) {
+        Ok(entries) => entries,
+        Err((line, message)) => {
+            report(line, Severity::Error, message);
+            return diagnostics;
+        }
+    };
+    if entries.is_empty() {
+        report(None, Severity::Error, "empty word list".to_string());
+        return diagnostics;
+    }
+
+    // Where each word first appears
+    let mut seen: HashMap<String, Option<usize>> = HashMap::new();
+    for Located { line, entry } in entries {
+        let word = &entry.word;
+        // Words are compared case-insensitively, as they are used in games
+        let upper = language.to_upper(word);
+        if let Some(c) = language::letters(&upper).find(|c| language.get_index(c).is_none()) {
+            report(
+                line,
+                Severity::Error,
+                format!("'{word}' contains '{c}', which is not in the {} alphabet", language.title),
+            );
+            continue;
+        }
+        if language::count_letters(word) != WORD_LENGTH {
+            report(
+                line,
+                Severity::Error,
+                format!("'{word}' should have {WORD_LENGTH} letters"),
+            );
+            continue;
+        }
+        if word.chars().any(char::is_uppercase) && word.chars().any(char::is_lowercase) {
+            report(
+                line,
+                Severity::Warning,
+                format!("'{word}' mixes upper and lower case"),
+            );
+        }
+
+        if let Some(first) = seen.get(&upper) {
+            let message = match first {
+                Some(first) => format!("'{word}' is a duplicate of line {first}"),
+                None => format!("'{word}' is a duplicate"),
+            };
+            report(line, Severity::Warning, message);
+            continue;
+        }
+        if acceptable.is_some_and(|acceptable| !acceptable.contains(&upper)) {
+            report(
+                line,
+                Severity::Error,
+                format!("'{word}' is not in the acceptable set"),
+            );
+        }
+        seen.insert(upper, line);
+    }
+    diagnostics
+}
+
+/// Read a word list file, in the format told by its extension
+fn read_to_string(path: &Path) -> Result<(Format, String), Diagnostic> {
+    fs::read_to_string(path)
+        .map(|contents| (Format::detect(path), contents))
+        .map_err(|e| Diagnostic {
+            source: path.display().to_string(),
+            line: None,
+            severity: Severity::Error,
+            message: format!("failed to read: {e}"),
+        })
+}
+
+/// Check a word list file, see `lint`
+pub fn lint_file(
+    path: &Path,
+    acceptable: Option<&HashSet<String>>,
+    language: &Language,
+) -> Vec<Diagnostic> {
+    match read_to_string(path) {
+        Ok((format, contents)) => lint(
+            &path.display().to_string(),
+            format,
+            &contents,
+            acceptable,
+            language,
+        ),
+        Err(diagnostic) => vec![diagnostic],
+    }
+}
+
+/// Check the acceptable and final word lists of a game,
+/// using builtin lists of the language for those not given
+pub fn lint_lists(
+    acceptable_set: Option<&Path>,
+    final_set: Option<&Path>,
+    language: &Language,
+) -> Vec<Diagnostic> {
+    let (mut diagnostics, acceptable) = match acceptable_set {
+        Some(path) => {
+            let diagnostics = lint_file(path, None, language);
+            // Final words are not checked against an acceptable list which is invalid itself
+            let acceptable = if has_errors(&diagnostics) {
+                None
+            } else {
+                read_entries(path, language)
+                    .ok()
+                    .map(|entries| entries.into_iter().map(|entry| entry.word).collect())
+            };
+            (diagnostics, acceptable)
+        }
+        None => (
+            lint(
+                "builtin acceptable set",
+                Format::Text,
+                &language.acceptable.join("\n"),
+                None,
+                language,
+            ),
+            Some(language.get_acceptable().into_iter().collect()),
+        ),
+    };
+
+    match final_set {
+        Some(path) => diagnostics.extend(lint_file(path, acceptable.as_ref(), language)),
+        None if acceptable_set.is_none() => diagnostics.extend(lint(
+            "builtin final set",
+            Format::Text,
+            &language.final_words.join("\n"),
+            acceptable.as_ref(),
+            language,
+        )),
+        None => (),
+    }
+
+    // Retired words are never answers, so there must be some words left
+    if let Some(path) = final_set.or(acceptable_set) {
+        if !has_errors(&diagnostics) {
+            let entries = read_entries(path, language).unwrap_or_default();
+            if entries.iter().all(|entry| entry.retired) {
+                diagnostics.push(Diagnostic {
+                    source: path.display().to_string(),
+                    line: None,
+                    severity: Severity::Error,
+                    message: "every word is retired, leaving no answers".to_string(),
+                });
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Read the entries of a word list file, with words in upper case of the language
+pub fn read_entries(path: &Path, language: &Language) -> Result<Vec<Entry>, String> {
+    let (format, contents) = read_to_string(path).map_err(|diagnostic| diagnostic.to_string())?;
+    let entries = parse(format, &contents).map_err(|(_, message)| message)?;
+    Ok(entries
+        .into_iter()
+        .map(|Located { entry, .. }| Entry {
+            word: language.to_upper(&entry.word),
+            ..entry
+        })
+        .collect())
+}
+
+/// Read the words of a word list file, in upper case.
+/// The word list should have been checked
+pub fn read_word_list(path: &Path, language: &Language) -> Vec<String> {
+    read_entries(path, language)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.word)
+        .collect()
+}
+
+/// Get the words of a list which can be answers, leaving out retired ones
+pub fn get_answers(entries: &[Entry]) -> Vec<String> {
+    entries
+        .iter()
+        .filter(|entry| !entry.retired)
+        .map(|entry| entry.word.clone())
+        .collect()
+}
+
+/// Collect metadata of words, later entries taking precedence
+pub fn get_metadata<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Metadata {
+    entries
+        .into_iter()
+        .filter(|entry| entry.has_metadata())
+        .map(|entry| (entry.word.clone(), entry.clone()))
+        .collect()
+}
+
+/// Read a word list, after checking it the same way as word lists of games
+pub fn load(path: &Path, language: &Language) -> Result<Vec<String>, String> {
+    let diagnostics = lint_file(path, None, language);
+    if has_errors(&diagnostics) {
+        return Err(format_errors(&diagnostics));
+    }
+    Ok(read_word_list(path, language))
+}
+
+/// Words only in either of two lists, sorted
+pub struct Diff {
+    pub only_left: Vec<String>,
+    pub only_right: Vec<String>,
+}
+
+/// Compare two word lists
+pub fn diff(left: &[String], right: &[String]) -> Diff {
+    let left: BTreeSet<&String> = left.iter().collect();
+    let right: BTreeSet<&String> = right.iter().collect();
+    Diff {
+        only_left: left.difference(&right).map(|word| word.to_string()).collect(),
+        only_right: right.difference(&left).map(|word| word.to_string()).collect(),
+    }
+}
+
+/// Merge word lists, sorting the words and removing duplicates
+pub fn merge(lists: &[Vec<String>]) -> Vec<String> {
+    let words: BTreeSet<&String> = lists.iter().flatten().collect();
+    words.into_iter().cloned().collect()
+}
+
+/// Remove builtin answers of a language from a word list, sorting the words and removing duplicates
+pub fn subtract_builtin(list: &[String], language: &Language) -> Vec<String> {
+    let builtin: HashSet<String> = language.get_final().into_iter().collect();
+    merge(&[list.to_vec()])
+        .into_iter()
+        .filter(|word| !builtin.contains(word))
+        .collect()
+}
+
+/// How often a letter is used in a word list
+pub struct LetterFrequency {
+    pub letter: &'static str,
+    pub total: usize,
+    pub positions: [usize; WORD_LENGTH],
+}
+
+/// Count how often each letter of the alphabet of a language is used, the most used first.
+/// Letters never used are left out
+pub fn letter_frequency(list: &[String], language: &Language) -> Vec<LetterFrequency> {
+    let mut frequency: Vec<LetterFrequency> = language
+        .alphabet
+        .iter()
+        .map(|&letter| LetterFrequency {
+            letter,
+            total: 0,
+            positions: [0; WORD_LENGTH],
+        })
+        .collect();
+    for word in list {
+        for (i, c) in language::letters(word).enumerate() {
+            // The word list should have been checked, so every letter is in the alphabet
+            let letter = &mut frequency[language.get_index(c).unwrap()];
+            letter.total += 1;
+            letter.positions[i] += 1;
+        }
+    }
+
+    frequency.retain(|letter| letter.total > 0);
+    // The sort is stable, so letters as frequent stay in alphabetical order
+    frequency.sort_by_key(|letter| Reverse(letter.total));
+    frequency
+}
+
+/// Print words one per line, in lower case as in word list files
+pub fn print_words(words: &[String]) {
+    for word in words {
+        println!("{}", word.to_lowercase());
+    }
+}
+
+/// Print the difference of two lists, in tty mode or as lines marked by - and +
+pub fn print_diff(diff: &Diff, left: &Path, right: &Path, is_tty: bool) {
+    if is_tty {
+        for (words, path) in [(&diff.only_left, left), (&diff.only_right, right)] {
+            println!(
+                "{}",
+                console::style(format!("Only in {} ({} words):", path.display(), words.len()))
+                    .bold()
+                    .blue()
+            );
+            for word in words {
+                println!("    {}", console::style(word.to_lowercase()).magenta());
+            }
+        }
+    } else {
+        for word in &diff.only_left {
+            println!("- {}", word.to_lowercase());
+        }
+        for word in &diff.only_right {
+            println!("+ {}", word.to_lowercase());
+        }
+    }
+}
+
+/// Print letter frequency, in tty mode or as plain lines
+pub fn print_letter_frequency(frequency: &[LetterFrequency], words: usize, is_tty: bool) {
+    if is_tty {
+        println!(
+            "{}",
+            console::style(format!("Letter frequency of {words} words:"))
+                .bold()
+                .yellow()
+        );
+        let positions: String = (1..=WORD_LENGTH).map(|i| format!("{i:>6}")).collect();
+        println!(
+            "{}",
+            console::style(format!("    Letter  {:>6}{positions}", "Total")).bold()
+        );
+        for letter in frequency {
+            let positions: String = letter.positions.iter().map(|n| format!("{n:>6}")).collect();
+            println!(
+                "    {}       {:>6}{positions}",
+                console::style(letter.letter).bold().magenta(),
+                letter.total
+            );
+        }
+    } else {
+        for letter in frequency {
+            let positions: Vec<String> = letter.positions.iter().map(|n| n.to_string()).collect();
+            println!("{} {} {}", letter.letter, letter.total, positions.join(" "));
+        }
+    }
+}
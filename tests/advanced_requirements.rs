@@ -0,0 +1,255 @@
+use ntest::timeout;
+
+mod common;
+use common::TestCase;
+
+#[test]
+#[timeout(2000)]
+fn test_09_player_profiles() {
+    // play as a new player and compare with existing ones
+    TestCase::read("09_01_player_profiles").run_and_compare_game_state();
+    // compare players without a state file
+    TestCase::read("09_02_compare_without_state").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_10_extended_stats() {
+    // analytics of the history after a failed game
+    TestCase::read("10_01_extended_stats").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_11_stats_filters() {
+    // fewer favorite words, counting games with answers of a length
+    TestCase::read("11_01_stats_filters").run_and_compare_game_state();
+    // filters without showing statistics
+    TestCase::read("11_02_filter_without_stats").run_and_expect_exit();
+    // details of games reloaded from JSON state files are matched, except in old games
+    TestCase::read("11_03_stats_filter_after_reload").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_12_achievements() {
+    // win in hard mode at once, with one of the achievements unlocked before
+    TestCase::read("12_01_achievements").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_13_lint_words() {
+    // duplicates and mixed case are only warnings
+    TestCase::read("13_01_lint_words_warnings").run_and_compare_result();
+    // invalid words fail the check
+    TestCase::read("13_02_lint_words_errors").run_and_expect_exit();
+    // games can be played with word lists with warnings
+    TestCase::read("13_03_play_with_warnings").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_14_words() {
+    // manage word lists
+    TestCase::read("14_01_words_diff").run_and_compare_result();
+    TestCase::read("14_02_words_merge").run_and_compare_result();
+    TestCase::read("14_03_words_subtract_builtin").run_and_compare_result();
+    TestCase::read("14_04_words_stats").run_and_compare_result();
+    // word lists are checked before used
+    TestCase::read("14_05_words_invalid_list").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_15_structured_word_lists() {
+    // retired words can be guessed, and tags of answers show in statistics
+    TestCase::read("15_01_json_word_list").run_and_compare_result();
+    TestCase::read("15_02_csv_word_list").run_and_compare_result();
+    // retired words are never answers
+    TestCase::read("15_03_retired_answer").run_and_expect_exit();
+    // diagnostics of CSV word lists have line numbers
+    TestCase::read("15_04_lint_csv").run_and_compare_result();
+    TestCase::read("15_05_lint_invalid_csv").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_16_answer_strategies() {
+    // the most frequent word comes first by far
+    TestCase::read("16_01_frequency_strategy").run_and_compare_result();
+    // the answer of the closest difficulty in the metadata
+    TestCase::read("16_02_difficulty_strategy").run_and_compare_result();
+    // difficulties not in the metadata are counted by the solver
+    TestCase::read("16_03_solver_difficulty").run_and_compare_result();
+    // answers in the history are skipped
+    TestCase::read("16_04_fresh_answers").run_and_compare_game_state();
+    // a target difficulty is required
+    TestCase::read("16_05_difficulty_without_target").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_17_packs() {
+    // answers of a pack can be guessed even if not in the acceptable set
+    TestCase::read("17_01_builtin_pack").run_and_compare_result();
+    TestCase::read("17_02_pack_dir").run_and_compare_result();
+    TestCase::read("17_03_list_packs").run_and_compare_result();
+    // packs in a directory are only found with --pack-dir
+    TestCase::read("17_04_unknown_pack").run_and_expect_exit();
+    // words of packs are checked like word lists
+    TestCase::read("17_05_invalid_pack").run_and_expect_exit();
+    // games of a pack reloaded from a JSON state file are counted in its statistics
+    TestCase::read("17_06_pack_stats_after_reload").run_and_compare_game_state();
+    // a malformed pack in the directory breaks neither other packs nor the list of packs
+    TestCase::read("17_07_pack_dir_with_broken_pack").run_and_compare_result();
+    TestCase::read("17_08_list_packs_with_broken_pack").run_and_compare_result();
+    // builtin packs are in English only
+    TestCase::read("17_09_pack_in_another_language").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_18_definitions() {
+    // entries of supplementary dictionaries take precedence over builtin ones
+    TestCase::read("18_01_supplementary_dictionary").run_and_compare_result();
+    // every word is looked up before exiting with failure
+    TestCase::read("18_02_undefined_word").run_and_expect_exit();
+    // words not defined are skipped silently in the game
+    TestCase::read("18_03_define_in_game").run_and_compare_result();
+    // flat and structured entries in a dictionary, with senses of both kinds in an entry
+    TestCase::read("18_04_mixed_dictionary_entries").run_and_compare_result();
+    // fields unknown to structured entries are refused
+    TestCase::read("18_05_broken_dictionary_entry").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_19_languages() {
+    // 'ß' has no upper case, so it can't be spelt as 'SS'
+    TestCase::read("19_01_german").run_and_compare_result();
+    // 'Ё' is a letter of its own
+    TestCase::read("19_02_russian").run_and_compare_result();
+    // accents are dropped in Greek upper case
+    TestCase::read("19_03_greek_case_folding").run_and_compare_result();
+    // word lists are checked against the alphabet of the language
+    TestCase::read("19_04_lint_language").run_and_compare_result();
+    TestCase::read("19_05_foreign_letters").run_and_expect_exit();
+    TestCase::read("19_06_unknown_language").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_20_accents() {
+    // guesses may be typed without accents, and are kept as spelt in the word list
+    TestCase::read("20_01_typed_without_accents").run_and_compare_result();
+    // accented letters differ from their base letters unless ignored
+    TestCase::read("20_02_accents_strict").run_and_compare_result();
+    TestCase::read("20_03_ignore_accents").run_and_compare_result();
+    // 'Ç' is typed as 'C' in Portuguese
+    TestCase::read("20_04_portuguese").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_21_tui() {
+    // the full-screen terminal UI can't run without a terminal
+    TestCase::read("21_01_tui_requires_terminal").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_22_jsonl_protocol() {
+    // requests and responses are JSON objects, one per line
+    TestCase::read("22_01_jsonl_game").run_and_compare_result();
+    TestCase::read("22_02_jsonl_random").run_and_compare_result();
+    // broken requests are answered with errors, without stopping the game
+    TestCase::read("22_03_jsonl_bad_requests").run_and_compare_result();
+    // a game given up by starting another one is counted as failed
+    TestCase::read("22_04_jsonl_new_game_gives_up").run_and_compare_result();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_23_serve() {
+    // a game played over HTTP, with statistics kept per player
+    TestCase::read("23_01_serve_game").run_server_and_compare_result();
+    // options of new games, and requests which can't be served
+    TestCase::read("23_02_serve_options").run_server_and_compare_result();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_24_race() {
+    // players racing by the protocol, seeing the patterns of each other
+    TestCase::read("24_01_race_protocol").run_race_and_compare_result();
+    // a player racing in the CLI
+    TestCase::read("24_02_race_player").run_race_player_and_compare_result();
+}
+
+#[test]
+#[timeout(3000)]
+fn test_25_replay() {
+    // spectators watching every guess of a race, which is recorded
+    TestCase::read("25_01_race_spectate").run_race_and_compare_result();
+    // the race recorded played back, verified with the word lists
+    TestCase::read("25_02_replay").run_and_compare_result();
+    // a replay whose guesses are not as played
+    TestCase::read("25_03_replay_tampered").run_and_expect_exit();
+    // a replay whose winner is not the one of the games played again
+    TestCase::read("25_05_replay_tampered_winner").run_and_expect_exit();
+    // guesses after a game is over, and of somebody not in the race
+    TestCase::read("25_06_replay_extra_guesses").run_and_expect_exit();
+    // replays of games hosted over HTTP, once they are over
+    TestCase::read("25_04_serve_replay").run_server_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_26_tournament() {
+    // participants playing fixed and seeded rounds in turn, ranked by guesses
+    TestCase::read("26_01_tournament").run_and_compare_result();
+    // a round with both an answer and a day
+    TestCase::read("26_02_tournament_errors").run_and_expect_exit();
+    // commands of the CLI in rounds, except those which would help unfairly
+    TestCase::read("26_03_tournament_commands").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_27_subcommands() {
+    // games played by the play subcommand, with a config file overridden
+    TestCase::read("27_01_play").run_and_compare_result();
+    // statistics shown without playing
+    TestCase::read("27_02_stats").run_and_compare_result();
+    // the next guess suggested by the feedback of former guesses
+    TestCase::read("27_03_solve").run_and_compare_result();
+    // options of games given before the subcommand
+    TestCase::read("27_04_options_before_subcommand").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_28_layered_config() {
+    // a config file in TOML
+    TestCase::read("28_01_config_toml").run_and_compare_result();
+    // a config file in YAML, overridden by environment variables and then by flags
+    TestCase::read("28_02_config_yaml").run_and_compare_result();
+    // effective settings, and where each one comes from
+    TestCase::read("28_03_config_show").run_and_compare_result();
+    // settings left out are shown with the values games take
+    TestCase::read("28_05_config_show_defaults").run_and_compare_result();
+    // config files of the user and of the project, and environment variables, turned off
+    TestCase::read("28_06_config_no_config").run_and_compare_result();
+    // a config file with an unknown setting
+    TestCase::read("28_04_config_invalid").run_and_expect_exit();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_29_repl_commands() {
+    // commands during a game and after it, including giving up
+    TestCase::read("29_01_repl_commands").run_and_compare_result();
+    // a game won in difficult mode, shared as colored squares
+    TestCase::read("29_02_repl_share").run_and_compare_result();
+}
@@ -2,14 +2,14 @@ use std::env::consts::EXE_EXTENSION;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::*;
+use std::net::TcpStream;
 use std::path::{Path, PathBuf};
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, ChildStdout, Command, Stdio};
 use std::sync::Once;
 
 use assert_json_diff::assert_json_eq;
 use lazy_static::lazy_static;
 use pretty_assertions::assert_eq;
-use serde_json;
 
 // The code was originally written by Jack O'Connor (@oconnor663)
 // Taken from https://github.com/oconnor663/os_pipe.rs/blob/f41c58e503e1efc5e4d0edfcd2e756b3a81b4232/src/lib.rs#L281-L314
@@ -41,7 +41,7 @@ fn build_and_find_path(name: &str) -> PathBuf {
     static CARGO_BUILD_ONCE: Once = Once::new();
     CARGO_BUILD_ONCE.call_once(|| {
         let mut build_command = Command::new("cargo");
-        build_command.args(&["build", "--quiet"]);
+        build_command.args(["build", "--quiet"]);
         if !cfg!(debug_assertions) {
             build_command.arg("--release");
         }
@@ -62,8 +62,72 @@ fn build_and_find_path(name: &str) -> PathBuf {
         .with_extension(EXE_EXTENSION)
 }
 
+/// Replace the values of a key anywhere in a JSON value, as they vary from run to run
+#[allow(dead_code)]
+fn mask_key(value: &mut serde_json::Value, key: &str) {
+    match value {
+        serde_json::Value::Object(object) => {
+            for (name, value) in object {
+                if name == key {
+                    *value = 0.into();
+                } else {
+                    mask_key(value, key);
+                }
+            }
+        }
+        serde_json::Value::Array(array) => array.iter_mut().for_each(|value| mask_key(value, key)),
+        _ => (),
+    }
+}
+
+/// Times taken in races vary from run to run, so they are left out
+#[allow(dead_code)]
+fn mask_times(message: &str) -> String {
+    let mut message: serde_json::Value = serde_json::from_str(message).unwrap();
+    mask_key(&mut message, "time_ms");
+    message.to_string()
+}
+
+/// Make a directory to run the program in, away from config files in the project,
+/// with a home of its own. Cases name files under `tests` and `target` relatively,
+/// so both are linked there
+fn make_run_dir() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("wordle-tests-{}", std::process::id()));
+    std::fs::create_dir_all(dir.join("home").join(".config")).unwrap();
+    for name in ["tests", "target"] {
+        let original = Path::new(name).canonicalize().unwrap();
+        let link = dir.join(name);
+        if !link.exists() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(original, link).unwrap();
+            #[cfg(windows)]
+            std::os::windows::fs::symlink_dir(original, link).unwrap();
+        }
+    }
+    dir
+}
+
 lazy_static! {
-    static ref EXE_PATH: PathBuf = build_and_find_path("wordle");
+    static ref EXE_PATH: PathBuf = build_and_find_path("wordle").canonicalize().unwrap();
+    static ref RUN_DIR: PathBuf = make_run_dir();
+}
+
+/// Prepare to run the program, isolated from config files of the user and of the project,
+/// and from `WORDLE_*` environment variables of whoever runs the tests
+fn wordle() -> Command {
+    let home = RUN_DIR.join("home");
+    let mut command = Command::new(EXE_PATH.as_os_str());
+    command
+        .current_dir(RUN_DIR.as_path())
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("APPDATA", &home);
+    for (key, _) in std::env::vars_os() {
+        if key.to_string_lossy().starts_with("WORDLE_") {
+            command.env_remove(key);
+        }
+    }
+    command
 }
 
 pub struct TestCase {
@@ -71,6 +135,8 @@ pub struct TestCase {
     arguments: Vec<String>,
     input: String,
     answer: String,
+    /// Environment variables, given as `KEY=VALUE` lines in an optional `.env` file
+    environment: Vec<(String, String)>,
 }
 
 impl TestCase {
@@ -83,6 +149,8 @@ impl TestCase {
         let in_content = std::fs::read_to_string(in_file).unwrap();
         let ans_content = std::fs::read_to_string(ans_file).unwrap();
         let args_content = std::fs::read_to_string(args_file).unwrap();
+        let env_content =
+            std::fs::read_to_string(case_dir.join(format!("{}.env", name))).unwrap_or_default();
 
         Self {
             name: name.to_string(),
@@ -94,12 +162,18 @@ impl TestCase {
                 .collect(),
             input: in_content,
             answer: ans_content,
+            environment: env_content
+                .lines()
+                .filter_map(|line| line.split_once('='))
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
         }
     }
 
     fn execute_program_and_feed_input(&self) -> Child {
-        let mut command = Command::new(EXE_PATH.as_os_str())
+        let mut command = wordle()
             .args(&self.arguments)
+            .envs(self.environment.iter().map(|(key, value)| (key, value)))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .spawn()
@@ -161,14 +235,179 @@ impl TestCase {
         self.run_and_compare_result();
 
         // load state and compare with answer
-        let run_state: serde_json::Value =
+        let mut run_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&run_state_file).unwrap())).unwrap();
-        let answer_state: serde_json::Value =
+        let mut answer_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&after_state_file).unwrap()))
                 .unwrap();
+        // games are dated when they are played, so only the presence of dates is compared
+        mask_key(&mut run_state, "date");
+        mask_key(&mut answer_state, "date");
         assert_json_eq!(run_state, answer_state);
     }
 
+    /// Run the program as a host, which tells where it listens first.
+    /// Its output is kept open, so that it is not stopped by a broken pipe
+    fn start_host(&self, prefix: &str) -> (Child, BufReader<ChildStdout>, String) {
+        let mut command = wordle()
+            .args(&self.arguments)
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to execute process");
+
+        let mut stdout = BufReader::new(command.stdout.take().unwrap());
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let address = line
+            .trim()
+            .strip_prefix(prefix)
+            .unwrap_or_else(|| panic!("case {} should start a host", self.name))
+            .to_string();
+        (command, stdout, address)
+    }
+
+    /// Run the program as a server, send the requests in the input, one per line as
+    /// `METHOD PATH BODY`, and compare the responses, one per line as `STATUS BODY`
+    #[allow(dead_code)]
+    pub fn run_server_and_compare_result(&self) {
+        let (mut command, _stdout, address) = self.start_host("Listening on http://");
+
+        let mut output = vec![];
+        for request in self.input.lines().filter(|line| !line.is_empty()) {
+            let mut parts = request.splitn(3, ' ');
+            let method = parts.next().unwrap();
+            let path = parts.next().unwrap();
+            let body = parts.next().unwrap_or("");
+
+            let mut stream = std::net::TcpStream::connect(&address).unwrap();
+            write!(
+                stream,
+                "{method} {path} HTTP/1.1\r\nHost: {address}\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            let status = response.split(' ').nth(1).unwrap().to_string();
+            let (_, body) = response.split_once("\r\n\r\n").unwrap();
+            // only replays are timed, whose keys are sorted once masked
+            if body.contains("\"time_ms\"") {
+                output.push(format!("{status} {}", mask_times(body)));
+            } else {
+                output.push(format!("{status} {body}"));
+            }
+        }
+        command.kill().unwrap();
+        command.wait().unwrap();
+
+        // compare result
+        assert_eq!(
+            output.join("\n").trim(),
+            self.answer.trim(),
+            "case {} incorrect",
+            self.name
+        );
+    }
+
+    /// Run the program as a race host, send the requests in the input, one per line as
+    /// `PLAYER REQUEST` on a connection of each player, and compare the messages received,
+    /// one per line as `PLAYER MESSAGE`
+    #[allow(dead_code)]
+    pub fn run_race_and_compare_result(&self) {
+        let (mut command, _stdout, address) = self.start_host("Listening on ");
+
+        let mut players: Vec<(String, BufReader<TcpStream>, TcpStream)> = vec![];
+        let mut output = vec![];
+        for request in self.input.lines().filter(|line| !line.is_empty()) {
+            let (player, request) = request.split_once(' ').unwrap();
+            let index = match players.iter().position(|(name, ..)| name == player) {
+                Some(index) => index,
+                None => {
+                    let stream = TcpStream::connect(&address).unwrap();
+                    let reader = BufReader::new(stream.try_clone().unwrap());
+                    players.push((player.to_string(), reader, stream));
+                    players.len() - 1
+                }
+            };
+            let (_, reader, stream) = &mut players[index];
+            // the host may have turned the player away
+            let _ = writeln!(stream, "{request}");
+
+            // the answer is waited for, so that the host takes requests in the order sent
+            let kind = serde_json::from_str::<serde_json::Value>(request)
+                .map(|request| request["type"].clone())
+                .unwrap_or_default();
+            let answers: &[&str] = if kind == "join" {
+                &["lobby", "error"]
+            } else if kind == "spectate" {
+                &["lobby", "watch", "error"]
+            } else {
+                &["feedback", "error"]
+            };
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    break;
+                }
+                let message = mask_times(line.trim());
+                output.push(format!("{player} {message}"));
+                let kind = serde_json::from_str::<serde_json::Value>(&message).unwrap()["type"].clone();
+                if answers.iter().any(|answer| kind == *answer) {
+                    break;
+                }
+            }
+        }
+
+        // the rest is read until the host is done
+        for (player, reader, _) in &mut players {
+            for line in reader.lines() {
+                let Ok(line) = line else { break };
+                output.push(format!("{player} {}", mask_times(line.trim())));
+            }
+        }
+        let _ = command.kill();
+        command.wait().unwrap();
+
+        // compare result
+        assert_eq!(
+            output.join("\n").trim(),
+            self.answer.trim(),
+            "case {} incorrect",
+            self.name
+        );
+    }
+
+    /// Run the program as a race host, and compare the output of a player joining it,
+    /// with the input typed
+    #[allow(dead_code)]
+    pub fn run_race_player_and_compare_result(&self) {
+        let (mut host, _stdout, address) = self.start_host("Listening on ");
+
+        let mut command = wordle()
+            .args(["--join", &address])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("failed to execute process");
+        command
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(self.input.as_bytes())
+            .unwrap();
+        let output = command.wait_with_output().expect("failed to wait on process");
+        let _ = host.kill();
+        host.wait().unwrap();
+
+        assert!(output.status.success(), "case {} should exit normally", self.name);
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap().trim(),
+            self.answer.trim(),
+            "case {} incorrect",
+            self.name
+        );
+    }
+
     pub fn run_and_expect_exit(&self) {
         let command = self.execute_program_and_feed_input();
         assert!(